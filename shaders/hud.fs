@@ -0,0 +1,165 @@
+#version 430 core
+
+out vec4 FragColor;
+
+in vec2 HudCoords;
+
+// Width of the bar divided by its height. Positions below are in units of
+// the bar height, from its bottom left.
+uniform float aspect;
+uniform float faceRadius;
+uniform int minesLeft;
+uniform int seconds;
+// 0 while playing, 1 once won, 2 once lost.
+uniform int face;
+
+// Segments of each digit: bit 0 is the top one, then clockwise, and bit 6
+// is the middle one.
+const int DIGITS[10] = int[10](
+    0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F
+);
+const int MINUS = 0x40;
+
+const float MARGIN = 0.2;
+const float DIGIT_WIDTH = 0.45;
+const float DISPLAY_WIDTH = 3.0 * DIGIT_WIDTH + 0.1;
+
+const vec3 barColor = vec3(0.75);
+const vec3 displayColor = vec3(0.05);
+const vec3 litColor = vec3(1.0, 0.1, 0.1);
+const vec3 unlitColor = vec3(0.25, 0.03, 0.03);
+const vec3 faceColor = vec3(1.0, 0.85, 0.1);
+const vec3 inkColor = vec3(0.0);
+
+float segmentDistance(vec2 p, vec2 a, vec2 b)
+{
+    vec2 pa = p - a;
+    vec2 ba = b - a;
+    float h = clamp(dot(pa, ba) / dot(ba, ba), 0.0, 1.0);
+
+    return length(pa - ba * h);
+}
+
+// The digit at `index` from the left of a 3 digit display of `value`.
+int digitSegments(int value, int index)
+{
+    if (value < 0) {
+        if (index == 0) {
+            return MINUS;
+        }
+        value = -value;
+    }
+
+    int place = index == 0 ? 100 : (index == 1 ? 10 : 1);
+
+    return DIGITS[(value / place) % 10];
+}
+
+// `p` is relative to the bottom left of a digit, which is DIGIT_WIDTH wide
+// and 0.7 high.
+vec3 digitColor(vec2 p, int segments)
+{
+    const vec2 ends[14] = vec2[14](
+        vec2(0.1, 0.62), vec2(0.35, 0.62),
+        vec2(0.35, 0.62), vec2(0.35, 0.35),
+        vec2(0.35, 0.35), vec2(0.35, 0.08),
+        vec2(0.1, 0.08), vec2(0.35, 0.08),
+        vec2(0.1, 0.35), vec2(0.1, 0.08),
+        vec2(0.1, 0.62), vec2(0.1, 0.35),
+        vec2(0.1, 0.35), vec2(0.35, 0.35)
+    );
+
+    vec3 color = displayColor;
+
+    for (int i = 0; i < 7; i++) {
+        if (segmentDistance(p, ends[2 * i], ends[2 * i + 1]) < 0.035) {
+            color = ((segments >> i) & 1) == 1 ? litColor : unlitColor;
+        }
+    }
+
+    return color;
+}
+
+vec3 displayColorAt(vec2 p, float left, int value, vec3 background)
+{
+    vec2 local = p - vec2(left, 0.15);
+
+    if (local.x < 0.0 || local.x > DISPLAY_WIDTH || local.y < 0.0
+            || local.y > 0.7) {
+        return background;
+    }
+
+    local.x -= 0.05;
+    int index = int(floor(local.x / DIGIT_WIDTH));
+
+    if (index < 0 || index > 2) {
+        return displayColor;
+    }
+
+    vec2 digit = vec2(local.x - float(index) * DIGIT_WIDTH, local.y);
+
+    return digitColor(digit, digitSegments(value, index));
+}
+
+// `p` is relative to the centre of the face, in units of its radius.
+vec3 faceColorAt(vec2 p, vec3 background)
+{
+    // Raised square button around the face.
+    if (max(abs(p.x), abs(p.y)) > 1.0) {
+        return background;
+    }
+    if (max(abs(p.x), abs(p.y)) > 0.92) {
+        return p.x - p.y < 0.0 ? vec3(0.95) : vec3(0.45);
+    }
+
+    p /= 0.8;
+    float r = length(p);
+
+    if (r > 1.0) {
+        return barColor;
+    }
+    if (r > 0.9) {
+        return inkColor;
+    }
+
+    vec2 leftEye = vec2(-0.33, 0.3);
+    vec2 rightEye = vec2(0.33, 0.3);
+
+    if (face == 2) {
+        // Crossed out eyes.
+        for (int i = 0; i < 2; i++) {
+            vec2 d = abs(p - (i == 0 ? leftEye : rightEye));
+            if (abs(d.x - d.y) < 0.07 && max(d.x, d.y) < 0.15) {
+                return inkColor;
+            }
+        }
+    } else if (face == 1) {
+        // Sunglasses.
+        if (length(p - leftEye) < 0.22 || length(p - rightEye) < 0.22
+                || (abs(p.y - 0.38) < 0.04 && abs(p.x) < 0.75)) {
+            return inkColor;
+        }
+    } else if (length(p - leftEye) < 0.1 || length(p - rightEye) < 0.1) {
+        return inkColor;
+    }
+
+    bool mouth = face == 2
+        ? abs(length(p - vec2(0.0, -0.85)) - 0.45) < 0.06 && p.y > -0.55
+        : abs(length(p - vec2(0.0, 0.1)) - 0.55) < 0.06 && p.y < -0.15;
+
+    return mouth ? inkColor : faceColor;
+}
+
+void main()
+{
+    vec2 p = HudCoords * vec2(aspect, 1.0);
+    vec3 color = barColor;
+
+    color = displayColorAt(p, MARGIN, minesLeft, color);
+    color = displayColorAt(
+        p, aspect - MARGIN - DISPLAY_WIDTH, seconds, color
+    );
+    color = faceColorAt((p - vec2(aspect / 2.0, 0.5)) / faceRadius, color);
+
+    FragColor = vec4(color, 1.0);
+}
\ No newline at end of file
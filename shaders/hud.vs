@@ -0,0 +1,12 @@
+#version 430 core
+
+out vec2 HudCoords;
+
+void main()
+{
+    // Covers the whole viewport with a triangle strip of 4 vertices.
+    vec2 corner = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+
+    HudCoords = corner;
+    gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
\ No newline at end of file
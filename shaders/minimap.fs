@@ -0,0 +1,61 @@
+#version 430 core
+
+out vec4 FragColor;
+
+in vec2 BoardCoords;
+
+uniform int boardWidth;
+uniform int boardHeight;
+// Left, bottom, right and top of the camera view, the board spanning -1 to
+// 1 on both axes.
+uniform vec4 viewArea;
+
+// Shared with the tile shader: x is the texture index of each tile.
+layout(std430, binding = 0) buffer TileSSBO {
+    vec2 tileData[];
+};
+
+const vec3 viewColor = vec3(1.0, 0.8, 0.0);
+const vec3 borderColor = vec3(0.1);
+
+vec3 tileColor(float textureIndex)
+{
+    if (textureIndex < 0.5) {
+        return vec3(0.55); // hidden
+    } else if (textureIndex < 1.5) {
+        return vec3(0.9, 0.5, 0.1); // flagged
+    } else if (textureIndex < 10.5) {
+        return vec3(0.85); // revealed
+    } else if (textureIndex < 11.5) {
+        return vec3(0.1); // mine
+    } else if (textureIndex < 12.5) {
+        return vec3(0.9, 0.1, 0.1); // exploded
+    }
+    return vec3(0.6, 0.2, 0.6); // wrong flag
+}
+
+void main()
+{
+    ivec2 boardSize = ivec2(boardWidth, boardHeight);
+    ivec2 tile = min(ivec2(BoardCoords * vec2(boardSize)), boardSize - 1);
+    vec3 color = tileColor(tileData[tile.x + tile.y * boardWidth].x);
+
+    vec2 position = BoardCoords * 2.0 - 1.0;
+    vec2 lineWidth = 2.0 * fwidth(position);
+
+    vec2 toView = min(abs(position - viewArea.xy), abs(position - viewArea.zw));
+    bool inView = all(greaterThanEqual(position, viewArea.xy - lineWidth))
+        && all(lessThanEqual(position, viewArea.zw + lineWidth));
+
+    if (inView && any(lessThan(toView, lineWidth))) {
+        color = viewColor;
+    }
+
+    vec2 toBorder = 1.0 - abs(position);
+
+    if (any(lessThan(toBorder, lineWidth))) {
+        color = borderColor;
+    }
+
+    FragColor = vec4(color, 0.9);
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+#version 430 core
+
+// Left, bottom, right and top of the minimap in the board viewport.
+uniform vec4 rect;
+
+out vec2 BoardCoords;
+
+void main()
+{
+    // Drawn as a triangle strip of 4 vertices, without any vertex buffer.
+    vec2 corner = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+
+    BoardCoords = corner;
+    gl_Position = vec4(mix(rect.xy, rect.zw, corner), -0.5, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+#version 430 core
+
+out vec4 FragColor;
+
+in vec2 TexCoords;
+in vec4 Color;
+
+// Distance to the glyph outline, 0.5 on the outline and growing inwards.
+uniform sampler2D fontAtlas;
+
+void main()
+{
+    float distance = texture(fontAtlas, TexCoords).r;
+    // About a pixel of antialiasing, whatever the size of the text.
+    float width = fwidth(distance) * 0.7;
+    float alpha = smoothstep(0.5 - width, 0.5 + width, distance);
+
+    FragColor = vec4(Color.rgb, Color.a * alpha);
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+#version 430 core
+
+layout (location = 0) in vec2 aPosition;
+layout (location = 1) in vec2 aTexCoords;
+layout (location = 2) in vec4 aColor;
+
+out vec2 TexCoords;
+out vec4 Color;
+
+// In pixels, like the vertex positions.
+uniform vec2 viewportSize;
+
+void main()
+{
+    TexCoords = aTexCoords;
+    Color = aColor;
+    gl_Position = vec4(aPosition / viewportSize * 2.0 - 1.0, 0.0, 1.0);
+}
\ No newline at end of file
@@ -2,19 +2,136 @@
 
 out vec4 FragColor;
 
-in vec2 TexCoords;
-in float Pos;
+in vec2 BoardCoords;
 
+uniform int boardWidth;
+uniform int boardHeight;
 uniform sampler2DArray atlasTexture;
+// Seconds, the clock animations start from.
+uniform float time;
+uniform bool animate;
 
-layout(std430, binding = 0) buffer TextureSSBO {
-    float textureData[];
+// One entry per tile: x is the texture index, y the highlights, as bits.
+layout(std430, binding = 0) buffer TileSSBO {
+    vec2 tileData[];
 };
 
-void main() 
-{  
-    vec3 texCoords = vec3(TexCoords.x, TexCoords.y, textureData[int(Pos)]);
-    vec4 color = texture(atlasTexture, texCoords);
+// One entry per tile: x is when its last animation started, y the kind of
+// animation, as bits.
+layout(std430, binding = 1) buffer AnimationSSBO {
+    vec2 animations[];
+};
+
+const int CURSOR = 1;
+const int HOVERED = 2;
+const int PRESSED = 4;
+
+const int REVEAL = 1;
+const int BOUNCE = 2;
+const int FLASH = 4;
+
+const float REVEAL_TIME = 0.15;
+const float BOUNCE_TIME = 0.3;
+const float FLASH_TIME = 0.5;
+
+const vec3 cursorColor = vec3(1.0, 0.8, 0.0);
+const float cursorBorder = 0.1;
+const float EMPTY_TILE = 2.0;
+const float pressedShadow = 0.08;
+const vec3 flashColor = vec3(1.0, 0.55, 0.1);
+const float PI = 3.14159265;
+
+// Where the pixel is in its tile, with the top left corner of the texture
+// at (0, 0).
+vec2 TexCoords;
+
+// Derivatives of the texture coordinates, taken before any branch since
+// they're undefined in branches some neighbouring pixels skip. The mipmap
+// of every sample is picked from them.
+vec2 dx;
+vec2 dy;
+
+vec4 sampleTile(vec2 coords, float layer, float scale)
+{
+    vec3 position = vec3(coords, layer);
+    return textureGrad(atlasTexture, position, dx * scale, dy * scale);
+}
+
+// The tile shrunk to `size` in its middle, over a hidden tile.
+vec4 scaled(float layer, float size)
+{
+    vec2 coords = (TexCoords - 0.5) / size + 0.5;
+
+    if (clamp(coords, 0.0, 1.0) != coords) {
+        return sampleTile(TexCoords, 0.0, 1.0);
+    }
+    return sampleTile(coords, layer, 1.0 / size);
+}
+
+void main()
+{
+    // Taken from the board coordinates, since the texture coordinates jump
+    // back at the edges of tiles and would pick the smallest mipmap there.
+    dx = dFdx(BoardCoords) * vec2(1.0, -1.0);
+    dy = dFdy(BoardCoords) * vec2(1.0, -1.0);
+
+    ivec2 size = ivec2(boardWidth, boardHeight);
+    ivec2 position = clamp(ivec2(floor(BoardCoords)), ivec2(0), size - 1);
+    int index = position.x + position.y * boardWidth;
+
+    TexCoords = BoardCoords - vec2(position);
+    TexCoords.y = 1.0 - TexCoords.y;
+
+    vec2 tile = tileData[index];
+    vec4 color = sampleTile(TexCoords, tile.x, 1.0);
+
+    if (animate) {
+        vec2 animation = animations[index];
+        float elapsed = time - animation.x;
+        int kind = int(animation.y);
+
+        if ((kind & REVEAL) != 0) {
+            if (elapsed < 0.0) {
+                // Waiting for the cascade to get here.
+                color = sampleTile(TexCoords, 0.0, 1.0);
+            } else if (elapsed < REVEAL_TIME) {
+                float progress = elapsed / REVEAL_TIME;
+                color = scaled(tile.x, mix(0.5, 1.0, sqrt(progress)));
+            }
+        }
+        if ((kind & BOUNCE) != 0 && elapsed >= 0.0 && elapsed < BOUNCE_TIME) {
+            float progress = elapsed / BOUNCE_TIME;
+            // Shrinks, then overshoots a little and settles.
+            float squash = sin(progress * 2.0 * PI) * (1.0 - progress);
+            color = scaled(tile.x, 1.0 - 0.25 * squash);
+        }
+        if ((kind & FLASH) != 0 && elapsed >= 0.0 && elapsed < FLASH_TIME) {
+            float strength = 1.0 - elapsed / FLASH_TIME;
+            color.rgb = mix(color.rgb, flashColor, 0.7 * strength * strength);
+        }
+    }
+
+    int highlight = int(tile.y);
+
+    if ((highlight & PRESSED) != 0) {
+        // Sunken: an empty revealed tile, shaded along the top and left.
+        color = sampleTile(TexCoords, EMPTY_TILE, 1.0);
+        if (min(TexCoords.x, TexCoords.y) < pressedShadow) {
+            color.rgb *= 0.6;
+        }
+    }
+    if ((highlight & HOVERED) != 0) {
+        color.rgb = mix(color.rgb, vec3(1.0), 0.15);
+    }
+    if ((highlight & CURSOR) != 0) {
+        vec2 edge = min(TexCoords, 1.0 - TexCoords);
+
+        if (min(edge.x, edge.y) < cursorBorder) {
+            color = vec4(cursorColor, 1.0);
+        } else {
+            color.rgb = mix(color.rgb, cursorColor, 0.2);
+        }
+    }
 
     FragColor = color;
 }
\ No newline at end of file
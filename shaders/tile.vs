@@ -1,15 +1,20 @@
 #version 430 core
 
-layout (location = 0) in vec2 aCoords;
-layout (location = 1) in vec2 aTexCoord;
-layout (location = 2) in float aPos;
+uniform int boardWidth;
+uniform int boardHeight;
 
-out vec2 TexCoords;
-out float Pos;
+// Zooms and pans the board, which spans -1 to 1 on both axes.
+uniform mat4 view;
+
+// Position on the board in tiles, from the bottom left corner.
+out vec2 BoardCoords;
 
 void main()
 {
-    gl_Position = vec4(aCoords, 0.0, 1.0);
-    TexCoords = aTexCoord;
-    Pos = aPos;
-}
\ No newline at end of file
+    // The whole board is one triangle strip of 4 vertices, without any
+    // vertex buffer. The fragment shader finds the tile under each pixel.
+    vec2 corner = vec2(gl_VertexID & 1, gl_VertexID >> 1);
+
+    BoardCoords = corner * vec2(boardWidth, boardHeight);
+    gl_Position = view * vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
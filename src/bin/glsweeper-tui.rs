@@ -0,0 +1,188 @@
+use std::{
+    io::{self, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    execute, queue,
+    style::{self, Color, Stylize},
+    terminal::{self, ClearType},
+};
+use glsweeper_rs::{
+    config::Config,
+    game::{Game, GameState, TextMode},
+    logger,
+};
+
+struct Terminal;
+
+impl Terminal {
+    fn new() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(Terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(
+            io::stdout(),
+            style::ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    logger::init();
+
+    // Loaded before entering the alternate screen, so warnings stay visible.
+    let config = Config::load();
+
+    let _terminal = Terminal::new()?;
+    let mut stdout = io::stdout();
+
+    let mut game = Game::new(config.width, config.height);
+    game.rules = config.rules;
+    game.set_mine_density(config.mine_density);
+    let (mut cursor_x, mut cursor_y) = (game.width / 2, game.height / 2);
+    // What the last save did, shown under the board.
+    let mut notice = String::new();
+
+    loop {
+        draw(&mut stdout, &game, cursor_x, cursor_y, &notice)?;
+
+        if !event::poll(Duration::from_millis(100))? {
+            continue;
+        }
+
+        let code = match event::read()? {
+            Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) => code,
+            _ => continue,
+        };
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => break,
+            KeyCode::Left | KeyCode::Char('h') => cursor_x -= 1,
+            KeyCode::Right | KeyCode::Char('l') => cursor_x += 1,
+            KeyCode::Up | KeyCode::Char('k') => cursor_y += 1,
+            KeyCode::Down | KeyCode::Char('j') => cursor_y -= 1,
+            KeyCode::Enter => game.reveal_at(cursor_x, cursor_y),
+            KeyCode::Char('f') => game.flag_at(cursor_x, cursor_y),
+            KeyCode::Char(' ') => game.chord_or_flag_at(cursor_x, cursor_y),
+            KeyCode::Char('r') => game.restart(),
+            KeyCode::Char('s') => notice = save(&game),
+            KeyCode::Char('+') | KeyCode::Char('=') => game
+                .increase_size(config.width_increment, config.height_increment),
+            KeyCode::Char('-') => game
+                .decrease_size(config.width_increment, config.height_increment),
+            _ => (),
+        }
+
+        cursor_x = cursor_x.clamp(0, game.width - 1);
+        cursor_y = cursor_y.clamp(0, game.height - 1);
+    }
+
+    Ok(())
+}
+
+fn draw(
+    out: &mut impl Write,
+    game: &Game,
+    cursor_x: isize,
+    cursor_y: isize,
+    notice: &str,
+) -> io::Result<()> {
+    queue!(
+        out,
+        cursor::MoveTo(0, 0),
+        style::Print(game.status().bold()),
+        terminal::Clear(ClearType::UntilNewLine),
+        cursor::MoveToNextLine(2)
+    )?;
+
+    let view = game.to_text(TextMode::PlayerView);
+
+    for (row, line) in view.lines().enumerate() {
+        let y = game.height - 1 - row as isize;
+
+        for (x, c) in line.chars().enumerate() {
+            let glyph = match c {
+                '0' => ' '.stylize(),
+                '#' => '#'.dark_grey(),
+                'F' => 'F'.red().bold(),
+                '*' => '*'.white().bold(),
+                'X' => 'X'.white().on_red(),
+                '!' => 'F'.dark_red().crossed_out(),
+                c => c.with(clue_colour(c)).bold(),
+            };
+            let glyph = if x as isize == cursor_x && y == cursor_y {
+                glyph.reverse()
+            } else {
+                glyph
+            };
+
+            queue!(out, style::PrintStyledContent(glyph), style::Print(' '))?;
+        }
+
+        queue!(
+            out,
+            terminal::Clear(ClearType::UntilNewLine),
+            cursor::MoveToNextLine(1)
+        )?;
+    }
+
+    queue!(
+        out,
+        cursor::MoveToNextLine(1),
+        style::Print(
+            "arrows/hjkl move | enter reveal | f flag | space chord | \
+             r new game | s save | +/- size | q quit"
+                .dark_grey()
+        ),
+        cursor::MoveToNextLine(1),
+        style::Print(notice),
+        terminal::Clear(ClearType::FromCursorDown)
+    )?;
+
+    out.flush()
+}
+
+/// Writes `game` to the save file, once its mines are placed, and tells how
+/// that went.
+fn save(game: &Game) -> String {
+    if game.state == GameState::Start {
+        return "Nothing to save before the first reveal".to_owned();
+    }
+    let path = match Game::save_path() {
+        Some(path) => path,
+        None => return "Failed to save the game: no data directory".to_owned(),
+    };
+
+    match game.save(&path) {
+        Ok(()) => format!("Saved the game to {}", path.display()),
+        Err(error) => format!("Failed to save {}: {}", path.display(), error),
+    }
+}
+
+fn clue_colour(clue: char) -> Color {
+    match clue {
+        '1' => Color::Blue,
+        '2' => Color::Green,
+        '3' => Color::Red,
+        '4' => Color::DarkBlue,
+        '5' => Color::DarkRed,
+        '6' => Color::Cyan,
+        '7' => Color::Magenta,
+        _ => Color::Grey,
+    }
+}
@@ -0,0 +1,267 @@
+use log::info;
+
+use crate::game::{Game, GameState, TileState, TileValue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    Reveal(isize, isize),
+    Flag(isize, isize),
+    Chord(isize, isize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisibleTile {
+    Hidden,
+    Flagged,
+    Revealed(u8),
+}
+
+/// What a player is allowed to see of a game in progress.
+pub struct BoardView<'a> {
+    game: &'a Game,
+}
+
+impl<'a> BoardView<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        BoardView { game }
+    }
+
+    pub fn width(&self) -> isize {
+        self.game.width
+    }
+
+    pub fn height(&self) -> isize {
+        self.game.height
+    }
+
+    pub fn mine_count(&self) -> isize {
+        self.game.mine_count
+    }
+
+    pub fn mines_left(&self) -> isize {
+        self.game.mines_left()
+    }
+
+    pub fn is_first_move(&self) -> bool {
+        self.game.state == GameState::Start
+    }
+
+    /// What the player sees of tile (`x`, `y`), or `None` off the board.
+    pub fn get(&self, x: isize, y: isize) -> Option<VisibleTile> {
+        if !self.game.contains(x, y) {
+            return None;
+        }
+        let tile = self.game.get_tile(x, y);
+
+        Some(match (tile.get_state(), tile.get_value()) {
+            (TileState::Flagged, _) => VisibleTile::Flagged,
+            (TileState::Revealed, TileValue::Empty(n)) => {
+                VisibleTile::Revealed(n)
+            }
+            _ => VisibleTile::Hidden,
+        })
+    }
+
+    pub fn neighbours(
+        &self,
+        x: isize,
+        y: isize,
+    ) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.game.neighbours(x, y)
+    }
+}
+
+pub trait Player {
+    fn next_move(&mut self, board: &BoardView) -> Move;
+}
+
+/// Flags and chords whatever single clues prove, and otherwise reveals the
+/// first hidden tile it finds.
+#[derive(Debug, Default)]
+pub struct SimpleSolver;
+
+impl Player for SimpleSolver {
+    fn next_move(&mut self, board: &BoardView) -> Move {
+        if board.is_first_move() {
+            return Move::Reveal(board.width() / 2, board.height() / 2);
+        }
+
+        let mut guess = None;
+
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                let clue = match board.get(x, y) {
+                    Some(VisibleTile::Revealed(clue)) if clue > 0 => {
+                        clue as usize
+                    }
+                    Some(VisibleTile::Hidden) => {
+                        guess.get_or_insert(Move::Reveal(x, y));
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                let (mut hidden, mut flagged) = (0, 0);
+
+                for (x, y) in board.neighbours(x, y) {
+                    match board.get(x, y) {
+                        Some(VisibleTile::Hidden) => hidden += 1,
+                        Some(VisibleTile::Flagged) => flagged += 1,
+                        _ => (),
+                    }
+                }
+
+                if hidden == 0 {
+                    continue;
+                }
+                if flagged == clue {
+                    return Move::Chord(x, y);
+                }
+                if flagged + hidden == clue {
+                    let (x, y) = board
+                        .neighbours(x, y)
+                        .find(|&(x, y)| {
+                            board.get(x, y) == Some(VisibleTile::Hidden)
+                        })
+                        .unwrap();
+                    return Move::Flag(x, y);
+                }
+            }
+        }
+
+        guess.unwrap_or(Move::Reveal(0, 0))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Simulation {
+    pub width: isize,
+    pub height: isize,
+    pub mine_count: isize,
+    pub games: u64,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SimulationReport {
+    pub games: u64,
+    pub wins: u64,
+    /// Average number of moves of the won games.
+    pub average_moves: f64,
+    /// Average 3BV per move of the won games.
+    pub average_three_bv_per_move: f64,
+}
+
+impl SimulationReport {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+}
+
+impl Simulation {
+    /// Plays every game of the simulation with `player`, without a window.
+    /// Game `i` is seeded with `seed + i`, so two players given the same
+    /// simulation face the same boards as long as they open on the same
+    /// tile.
+    pub fn run(&self, player: &mut impl Player) -> SimulationReport {
+        let mut report = SimulationReport {
+            games: self.games,
+            ..Default::default()
+        };
+        let mut total_moves = 0;
+        let mut total_three_bv_per_move = 0.0;
+
+        for i in 0..self.games {
+            let mut game = Game::with_seed(
+                self.width,
+                self.height,
+                self.seed.wrapping_add(i),
+            );
+            game.set_mine_count(self.mine_count);
+
+            // A move that changes nothing would otherwise loop forever.
+            let max_moves = 2 * self.width * self.height;
+            let mut moves = 0;
+
+            while moves < max_moves {
+                if matches!(game.state, GameState::Won(_) | GameState::Lost(_))
+                {
+                    break;
+                }
+                moves += 1;
+
+                match player.next_move(&BoardView::new(&game)) {
+                    Move::Reveal(x, y) => game.reveal_at(x, y),
+                    Move::Flag(x, y) => game.flag_at(x, y),
+                    Move::Chord(x, y) => game.chord_at(x, y),
+                }
+            }
+
+            if let GameState::Won(_) = game.state {
+                report.wins += 1;
+                total_moves += moves;
+                total_three_bv_per_move +=
+                    game.three_bv() as f64 / moves as f64;
+            }
+        }
+
+        if report.wins > 0 {
+            report.average_moves = total_moves as f64 / report.wins as f64;
+            report.average_three_bv_per_move =
+                total_three_bv_per_move / report.wins as f64;
+        }
+
+        info!(
+            "Simulated {} games: {:.1}% won",
+            report.games,
+            report.win_rate() * 100.0
+        );
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIMULATION: Simulation = Simulation {
+        width: 9,
+        height: 9,
+        mine_count: 10,
+        games: 200,
+        seed: 1,
+    };
+
+    #[test]
+    fn simulations_are_deterministic() {
+        let first = SIMULATION.run(&mut SimpleSolver);
+        let second = SIMULATION.run(&mut SimpleSolver);
+
+        assert_eq!(first.wins, second.wins);
+        assert_eq!(first.average_moves, second.average_moves);
+    }
+
+    // A change of rules or of how boards are generated shows up here.
+    #[test]
+    fn simple_solver_win_rate_is_stable() {
+        let report = SIMULATION.run(&mut SimpleSolver);
+
+        assert_eq!(report.games, 200);
+        assert!((0.75..=0.95).contains(&report.win_rate()));
+        assert!(report.average_three_bv_per_move > 0.0);
+    }
+
+    #[test]
+    fn board_view_has_nothing_off_the_board() {
+        let game = Game::from_text("*1\n11\n", None).unwrap();
+        let view = BoardView::new(&game);
+
+        assert_eq!(view.get(1, 0), Some(VisibleTile::Hidden));
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.get(0, -1), None);
+    }
+}
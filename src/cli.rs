@@ -0,0 +1,292 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use clap::{error::ErrorKind, CommandFactory, Parser, ValueEnum};
+
+use crate::{
+    config::Config,
+    game::{self, Game},
+    graphics::debug::DebugMode,
+    MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    /// 9x9 with 10 mines
+    Beginner,
+    /// 16x16 with 40 mines
+    Intermediate,
+    /// 30x16 with 99 mines
+    Expert,
+}
+
+impl Preset {
+    pub fn board(&self) -> (isize, isize, isize) {
+        match self {
+            Preset::Beginner => (9, 9, 10),
+            Preset::Intermediate => (16, 16, 40),
+            Preset::Expert => (30, 16, 99),
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Minesweeper clone using OpenGL")]
+pub struct Options {
+    /// Board size and mine count of a classic difficulty
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with_all = ["width", "height", "mines", "density"]
+    )]
+    pub preset: Option<Preset>,
+
+    /// Board width in tiles
+    #[arg(long, short)]
+    pub width: Option<isize>,
+
+    /// Board height in tiles
+    #[arg(long, short = 'H')]
+    pub height: Option<isize>,
+
+    /// Number of mines
+    #[arg(long, short, conflicts_with = "density")]
+    pub mines: Option<isize>,
+
+    /// Share of tiles holding a mine, between 0 and 1
+    #[arg(long)]
+    pub density: Option<f64>,
+
+    /// Seed for mine placement, to replay the same boards
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Window width in pixels
+    #[arg(long)]
+    pub window_width: Option<u32>,
+
+    /// Window height in pixels
+    #[arg(long)]
+    pub window_height: Option<u32>,
+
+    /// Start in fullscreen on the primary monitor
+    #[arg(long)]
+    pub fullscreen: bool,
+
+    /// Build the shaders from DIR instead of the bundled ones, rebuilding
+    /// them as they change, for development
+    #[arg(
+        long,
+        value_name = "DIR",
+        num_args = 0..=1,
+        default_missing_value = "shaders"
+    )]
+    pub watch_shaders: Option<PathBuf>,
+
+    /// Report GL problems in the log, as debug output by default. Debug
+    /// builds always have debug output
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "output"
+    )]
+    pub gl_debug: Option<DebugMode>,
+
+    /// Saved game to open instead of starting a new one
+    ///
+    /// Only saved games can be opened: games aren't recorded move by move,
+    /// so there are no replay files yet.
+    #[arg(
+        value_name = "FILE",
+        conflicts_with_all = [
+            "preset", "width", "height", "mines", "density", "seed"
+        ]
+    )]
+    pub open: Option<PathBuf>,
+}
+
+impl Options {
+    /// Parses the command line, exiting with a usage message when an option
+    /// is out of range. Options left out fall back to `config`.
+    pub fn parse_args(config: &Config) -> Self {
+        let options = Self::parse();
+
+        if let Err(message) = options.validate(config) {
+            Self::command()
+                .error(ErrorKind::ValueValidation, message)
+                .exit();
+        }
+
+        options
+    }
+
+    fn validate(&self, config: &Config) -> Result<(), String> {
+        let (width, height) = self.board_size(config);
+
+        if !(MIN_WIDTH..=MAX_WIDTH).contains(&width) {
+            return Err(format!(
+                "width must be between {} and {}, got {}",
+                MIN_WIDTH, MAX_WIDTH, width
+            ));
+        }
+        if !(MIN_HEIGHT..=MAX_HEIGHT).contains(&height) {
+            return Err(format!(
+                "height must be between {} and {}, got {}",
+                MIN_HEIGHT, MAX_HEIGHT, height
+            ));
+        }
+
+        if let Some(density) = self.density {
+            if !(0.0..=1.0).contains(&density) {
+                return Err(format!(
+                    "density must be between 0 and 1, got {}",
+                    density
+                ));
+            }
+        }
+
+        let max_mines = config.rules.first_click.max_mines(width, height);
+        if let Some(mines) = self.mines {
+            if !(0..=max_mines).contains(&mines) {
+                return Err(format!(
+                    "a {}x{} board holds between 0 and {} mines, got {}",
+                    width, height, max_mines, mines
+                ));
+            }
+        }
+        // The density only counts without a mine count, which takes
+        // precedence.
+        if let (Some(density), None, None) =
+            (self.density, self.mines, self.preset)
+        {
+            let mines = game::density_mine_count(width, height, density);
+            if mines > max_mines {
+                return Err(format!(
+                    "a density of {} puts {} mines on a {}x{} board, which \
+                     holds at most {}",
+                    density, mines, width, height, max_mines
+                ));
+            }
+        }
+
+        if self.window_width == Some(0) || self.window_height == Some(0) {
+            return Err("window size must be at least 1x1".to_owned());
+        }
+
+        Ok(())
+    }
+
+    pub fn board_size(&self, config: &Config) -> (isize, isize) {
+        match self.preset {
+            Some(preset) => {
+                let (width, height, _) = preset.board();
+                (width, height)
+            }
+            None => (
+                self.width.unwrap_or(config.width),
+                self.height.unwrap_or(config.height),
+            ),
+        }
+    }
+
+    pub fn window_size(&self, config: &Config) -> (u32, u32) {
+        (
+            self.window_width.unwrap_or(config.window_width),
+            self.window_height.unwrap_or(config.window_height),
+        )
+    }
+
+    pub fn gl_debug(&self) -> Option<DebugMode> {
+        self.gl_debug
+            .or(cfg!(debug_assertions).then_some(DebugMode::Output))
+    }
+
+    pub fn new_game(&self, config: &Config) -> Result<Game, Box<dyn Error>> {
+        if let Some(path) = &self.open {
+            let text = fs::read_to_string(path).map_err(|error| {
+                format!("failed to read {}: {}", path.display(), error)
+            })?;
+            let mut game = Game::from_save(&text).map_err(|error| {
+                format!("failed to load {}: {}", path.display(), error)
+            })?;
+            game.rules = config.rules;
+
+            return Ok(game);
+        }
+
+        let (width, height) = self.board_size(config);
+        let mut game = match self.seed {
+            Some(seed) => Game::with_seed(width, height, seed),
+            None => Game::new(width, height),
+        };
+
+        game.rules = config.rules;
+        game.set_mine_density(config.mine_density);
+
+        if let Some(preset) = self.preset {
+            game.set_mine_count(preset.board().2);
+        } else if let Some(mines) = self.mines {
+            game.set_mine_count(mines);
+        } else if let Some(density) = self.density {
+            game.set_mine_density(density);
+        }
+
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(args: &[&str]) -> Result<(), String> {
+        let args = ["glsweeper-rs"].iter().chain(args);
+        Options::try_parse_from(args)
+            .unwrap()
+            .validate(&Config::default())
+    }
+
+    #[test]
+    fn accepts_presets_and_custom_boards() {
+        assert_eq!(validate(&[]), Ok(()));
+        assert_eq!(validate(&["--preset", "expert"]), Ok(()));
+        assert_eq!(validate(&["-w", "30", "-H", "16", "-m", "99"]), Ok(()));
+        assert_eq!(validate(&["--density", "0"]), Ok(()));
+    }
+
+    #[test]
+    fn rejects_sizes_out_of_range() {
+        let too_tall = (MAX_HEIGHT + 1).to_string();
+
+        assert!(validate(&["--width", "0"]).is_err());
+        assert!(validate(&["--height", &too_tall]).is_err());
+        assert!(validate(&["--window-width", "0"]).is_err());
+    }
+
+    #[test]
+    fn rejects_more_mines_than_the_board_holds() {
+        let too_many = ["-w", "9", "-H", "9", "--mines", "73"];
+
+        assert!(validate(&too_many).is_err());
+        assert!(validate(&["-w", "9", "-H", "9", "--density", "1"]).is_err());
+        assert!(validate(&["--density", "1.5"]).is_err());
+        assert!(validate(&["--mines=-1"]).is_err());
+    }
+
+    #[test]
+    fn rejects_conflicting_options() {
+        let args = ["glsweeper-rs", "--preset", "beginner", "--mines", "5"];
+
+        assert!(Options::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn starts_a_board_without_mines() {
+        let options =
+            Options::try_parse_from(["glsweeper-rs", "--mines", "0"]).unwrap();
+        let game = options.new_game(&Config::default()).unwrap();
+
+        assert_eq!(game.mine_count, 0);
+    }
+}
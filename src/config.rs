@@ -0,0 +1,425 @@
+use std::{fs, io, ops::RangeInclusive, path::PathBuf};
+
+use log::{info, warn};
+use toml::{Table, Value};
+
+use crate::{
+    game::{Chording, FirstClick, Rules},
+    graphics::{
+        input::{Action, Bindings, Input},
+        texture::Filter,
+    },
+    DEFAULT_HEIGHT, DEFAULT_MINE_DENSITY, DEFAULT_WIDTH, DEFAULT_WINDOW_HEIGHT,
+    DEFAULT_WINDOW_WIDTH, HEIGHT_INCREMENT, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT,
+    MIN_WIDTH, WIDTH_INCREMENT,
+};
+
+/// User settings, read from `config.toml` in the `glsweeper-rs` folder of
+/// the platform's config directory:
+///
+/// ```toml
+/// [board]
+/// width = 21
+/// height = 21
+/// mine_density = 0.2
+/// width_increment = 5
+/// height_increment = 5
+///
+/// [rules]
+/// first_click = "safe-area" # or "safe-tile", "unprotected"
+/// chording = "click"        # or "key-only", "disabled"
+///
+/// [window]
+/// width = 800
+/// height = 896
+/// background = [0.3, 0.3, 0.3, 1.0]
+/// theme = "Default" # or the name or path of a theme, see `Theme`
+/// texture_filter = "trilinear" # or "linear", "nearest"
+/// animations = true
+///
+/// [gamepad]
+/// deadzone = 0.3
+/// repeat_delay = 0.3     # seconds before a held direction repeats
+/// repeat_interval = 0.1  # seconds between repeats
+///
+/// [controls]
+/// reveal = ["mouse-left", "enter"]
+/// flag = ["mouse-right", "f"]
+/// chord = ["space"]
+/// cursor_up = ["up", "k"]
+/// cursor_down = ["down", "j"]
+/// cursor_left = ["left", "h"]
+/// cursor_right = ["right", "l"]
+/// pan = ["mouse-middle"]
+/// zoom_in = ["page-up"]
+/// zoom_out = ["page-down"]
+/// fit_board = ["home"]
+/// restart = ["r"]
+/// increase_size = ["+", "="]
+/// decrease_size = ["-"]
+/// wireframe = ["w"]
+/// save = ["s"]
+/// pause = ["p", "pause"]
+/// menu = ["escape", "gamepad-start"]
+/// quit = []
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub width: isize,
+    pub height: isize,
+    pub mine_density: f64,
+    pub width_increment: isize,
+    pub height_increment: isize,
+    pub rules: Rules,
+    pub window_width: u32,
+    pub window_height: u32,
+    pub background: [f32; 4],
+    /// The name or path of the theme to start with.
+    pub theme: Option<String>,
+    pub texture_filter: Filter,
+    /// Whether tiles animate as they are revealed and flagged.
+    pub animations: bool,
+    pub gamepad_deadzone: f32,
+    pub gamepad_repeat_delay: f64,
+    pub gamepad_repeat_interval: f64,
+    pub bindings: Bindings,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            mine_density: DEFAULT_MINE_DENSITY,
+            width_increment: WIDTH_INCREMENT,
+            height_increment: HEIGHT_INCREMENT,
+            rules: Rules::default(),
+            window_width: DEFAULT_WINDOW_WIDTH,
+            window_height: DEFAULT_WINDOW_HEIGHT,
+            background: [0.3, 0.3, 0.3, 1.0],
+            theme: None,
+            texture_filter: Filter::default(),
+            animations: true,
+            gamepad_deadzone: 0.3,
+            gamepad_repeat_delay: 0.3,
+            gamepad_repeat_interval: 0.1,
+            bindings: Bindings::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir()
+            .map(|dir| dir.join("glsweeper-rs").join("config.toml"))
+    }
+
+    /// Loads the user's config, falling back to the defaults for anything
+    /// missing or invalid.
+    pub fn load() -> Self {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                info!("Loading config from {}", path.display());
+                Self::parse(&text)
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                Self::default()
+            }
+            Err(error) => {
+                warn!("Failed to read {}: {}", path.display(), error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut config = Self::default();
+
+        let table = match text.parse::<Table>() {
+            Ok(table) => table,
+            Err(error) => {
+                warn!("Ignoring config, it is not valid TOML: {}", error);
+                return config;
+            }
+        };
+
+        for (section_name, section) in table {
+            let section = match section {
+                Value::Table(section) => section,
+                _ => {
+                    warn!("Ignoring config key `{}`", section_name);
+                    continue;
+                }
+            };
+
+            for (key, value) in section {
+                if let Err(message) = config.set(&section_name, &key, &value) {
+                    warn!(
+                        "Ignoring config key `{}.{}`: {}",
+                        section_name, key, message
+                    );
+                }
+            }
+        }
+
+        config
+    }
+
+    fn set(
+        &mut self,
+        section: &str,
+        key: &str,
+        value: &Value,
+    ) -> Result<(), String> {
+        match (section, key) {
+            ("board", "width") => {
+                self.width = integer(value, MIN_WIDTH..=MAX_WIDTH)?;
+            }
+            ("board", "height") => {
+                self.height = integer(value, MIN_HEIGHT..=MAX_HEIGHT)?;
+            }
+            ("board", "mine_density") => {
+                self.mine_density = float(value, 0.0..=1.0)?;
+            }
+            ("board", "width_increment") => {
+                self.width_increment = integer(value, 1..=MAX_WIDTH)?;
+            }
+            ("board", "height_increment") => {
+                self.height_increment = integer(value, 1..=MAX_HEIGHT)?;
+            }
+            ("rules", "first_click") => {
+                self.rules.first_click = choice(
+                    value,
+                    &[
+                        ("safe-area", FirstClick::SafeArea),
+                        ("safe-tile", FirstClick::SafeTile),
+                        ("unprotected", FirstClick::Unprotected),
+                    ],
+                )?;
+            }
+            ("rules", "chording") => {
+                self.rules.chording = choice(
+                    value,
+                    &[
+                        ("click", Chording::Click),
+                        ("key-only", Chording::KeyOnly),
+                        ("disabled", Chording::Disabled),
+                    ],
+                )?;
+            }
+            ("window", "width") => {
+                self.window_width = integer(value, 1..=u32::MAX)?;
+            }
+            ("window", "height") => {
+                self.window_height = integer(value, 1..=u32::MAX)?;
+            }
+            ("window", "background") => self.background = colour(value)?,
+            ("window", "theme") => {
+                self.theme = Some(
+                    value
+                        .as_str()
+                        .ok_or_else(|| "expected a theme name".to_owned())?
+                        .to_owned(),
+                );
+            }
+            ("window", "texture_filter") => {
+                self.texture_filter = choice(
+                    value,
+                    &[
+                        ("nearest", Filter::Nearest),
+                        ("linear", Filter::Linear),
+                        ("trilinear", Filter::Trilinear),
+                    ],
+                )?;
+            }
+            ("window", "animations") => {
+                self.animations = value
+                    .as_bool()
+                    .ok_or_else(|| "expected true or false".to_owned())?;
+            }
+            ("gamepad", "deadzone") => {
+                self.gamepad_deadzone = float(value, 0.0..=1.0)? as f32;
+            }
+            ("gamepad", "repeat_delay") => {
+                self.gamepad_repeat_delay = float(value, 0.0..=10.0)?;
+            }
+            ("gamepad", "repeat_interval") => {
+                self.gamepad_repeat_interval = float(value, 0.01..=10.0)?;
+            }
+            ("controls", name) => {
+                let action = Action::from_name(name)
+                    .ok_or_else(|| "unknown action".to_owned())?;
+                self.bindings.bind(action, &inputs(value)?);
+            }
+            _ => return Err("unknown key".to_owned()),
+        }
+
+        Ok(())
+    }
+}
+
+fn integer<T>(value: &Value, range: RangeInclusive<T>) -> Result<T, String>
+where
+    T: TryFrom<i64> + PartialOrd + std::fmt::Display,
+{
+    let out_of_range = || {
+        format!(
+            "expected a whole number between {} and {}",
+            range.start(),
+            range.end()
+        )
+    };
+
+    match value {
+        Value::Integer(n) => T::try_from(*n)
+            .ok()
+            .filter(|n| range.contains(n))
+            .ok_or_else(out_of_range),
+        _ => Err(out_of_range()),
+    }
+}
+
+fn float(value: &Value, range: RangeInclusive<f64>) -> Result<f64, String> {
+    let n = match value {
+        Value::Float(n) => *n,
+        Value::Integer(n) => *n as f64,
+        _ => f64::NAN,
+    };
+
+    if range.contains(&n) {
+        Ok(n)
+    } else {
+        Err(format!(
+            "expected a number between {} and {}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+fn choice<T: Copy>(value: &Value, choices: &[(&str, T)]) -> Result<T, String> {
+    choices
+        .iter()
+        .find(|(name, _)| value.as_str() == Some(name))
+        .map(|&(_, choice)| choice)
+        .ok_or_else(|| {
+            let names: Vec<String> = choices
+                .iter()
+                .map(|(name, _)| format!("\"{}\"", name))
+                .collect();
+            format!("expected one of {}", names.join(", "))
+        })
+}
+
+fn inputs(value: &Value) -> Result<Vec<Input>, String> {
+    match value {
+        Value::String(name) => Ok(vec![Input::from_name(name)?]),
+        Value::Array(names) => names
+            .iter()
+            .map(|name| match name.as_str() {
+                Some(name) => Input::from_name(name),
+                None => Err("expected a list of key names".to_owned()),
+            })
+            .collect(),
+        _ => Err("expected a list of key names".to_owned()),
+    }
+}
+
+pub fn colour(value: &Value) -> Result<[f32; 4], String> {
+    let error = || {
+        "expected 3 or 4 numbers between 0 and 1, as in [0.3, 0.3, 0.3]"
+            .to_owned()
+    };
+
+    let components = match value.as_array() {
+        Some(components) if (3..=4).contains(&components.len()) => components,
+        _ => return Err(error()),
+    };
+
+    let mut colour = [1.0; 4];
+    for (channel, component) in colour.iter_mut().zip(components) {
+        *channel = float(component, 0.0..=1.0).map_err(|_| error())? as f32;
+    }
+
+    Ok(colour)
+}
+
+#[cfg(test)]
+mod tests {
+    use glfw::Key;
+
+    use super::*;
+
+    #[test]
+    fn reads_every_section() {
+        let config = Config::parse(
+            r#"
+            [board]
+            width = 30
+            height = 16
+            mine_density = 0.15
+
+            [rules]
+            first_click = "unprotected"
+            chording = "key-only"
+
+            [window]
+            background = [0.1, 0.2, 0.3]
+            texture_filter = "nearest"
+
+            [controls]
+            restart = ["f5", "n"]
+            "#,
+        );
+
+        assert_eq!((config.width, config.height), (30, 16));
+        assert_eq!(config.mine_density, 0.15);
+        assert_eq!(config.rules.first_click, FirstClick::Unprotected);
+        assert_eq!(config.rules.chording, Chording::KeyOnly);
+        assert_eq!(config.background, [0.1, 0.2, 0.3, 1.0]);
+        assert_eq!(config.texture_filter, Filter::Nearest);
+        assert_eq!(
+            config.bindings.inputs(Action::Restart),
+            [Input::Key(Key::F5), Input::Char('n')]
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_keys() {
+        let config = Config::parse(
+            "[board]\nwidth = 12\ncolour = 3\n[cheats]\nlives = 9\n",
+        );
+
+        assert_eq!(config.width, 12);
+        assert_eq!(config.height, DEFAULT_HEIGHT);
+    }
+
+    #[test]
+    fn falls_back_on_values_out_of_range() {
+        let config = Config::parse(&format!(
+            "[board]\nwidth = {}\nheight = 0\nmine_density = 2.0\n\
+             [window]\nwidth = -5\n[rules]\nchording = \"sometimes\"\n",
+            MAX_WIDTH + 1
+        ));
+
+        assert_eq!(
+            (config.width, config.height),
+            (DEFAULT_WIDTH, DEFAULT_HEIGHT)
+        );
+        assert_eq!(config.mine_density, DEFAULT_MINE_DENSITY);
+        assert_eq!(config.window_width, DEFAULT_WINDOW_WIDTH);
+        assert_eq!(config.rules.chording, Chording::default());
+    }
+
+    #[test]
+    fn falls_back_on_invalid_toml() {
+        let config = Config::parse("[board\nwidth = 12");
+
+        assert_eq!(config.width, DEFAULT_WIDTH);
+    }
+}
@@ -1,26 +1,42 @@
-use std::{cell::RefCell, rc::Rc, time};
+use std::{collections::VecDeque, sync::mpsc::Receiver, time};
 
 use log::info;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 use crate::{
-    graphics::gl_wrapper::VAO, HEIGHT_INCREMENT, MAX_HEIGHT, MAX_WIDTH,
-    MIN_HEIGHT, MIN_WIDTH, WIDTH_INCREMENT,
+    graphics::camera::Camera, DEFAULT_MINE_DENSITY, MAX_HEIGHT, MAX_WIDTH,
+    MIN_HEIGHT, MIN_WIDTH,
 };
 
 use self::{
+    board::Board,
+    event::EventQueue,
+    tile_drawer::{Highlight, TileDrawer},
+};
+
+pub use self::{
+    clock::Clock,
+    event::GameEvent,
+    rules::{Chording, FirstClick, Rules},
+    text::{ParseError, TextMode},
     tile::{Tile, TileState, TileValue},
-    tile_drawer::TileDrawer,
 };
 
+mod animation;
+mod board;
+mod clock;
 mod coordinates;
-mod draw;
+mod event;
+mod metrics;
+mod rules;
+mod text;
 mod tile;
 mod tile_drawer;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum GameState {
     Start,
-    Playing(time::Instant),
+    Playing(Clock),
     Won(time::Duration),
     Lost(time::Duration),
 }
@@ -30,46 +46,115 @@ pub struct Game {
     pub width: isize,
     pub height: isize,
     pub mine_count: isize,
-    tiles: Vec<tile::Tile>,
-    _vao: VAO,
-    tile_drawer: TileDrawer,
-    tiles_changed: Rc<RefCell<Vec<isize>>>,
+    pub rules: Rules,
+    /// Tile highlighted for keyboard play, hidden while the mouse is used.
+    pub cursor: Option<(isize, isize)>,
+    /// Tile under the mouse pointer, highlighted while there's no cursor.
+    pub hovered: Option<(isize, isize)>,
+    /// Tiles drawn sunken while a mouse button that reveals or chords is
+    /// held on the board. Their state doesn't change.
+    pub pressed: Vec<(isize, isize)>,
+    /// Whether the game was opened from text rather than dealt. Its clock
+    /// started over when it was opened, so its time means nothing.
+    loaded: bool,
+    mine_density: f64,
+    board: Board,
+    tile_drawer: Option<TileDrawer>,
+    events: EventQueue,
+    rng: StdRng,
 }
 
 impl Game {
     pub fn new(width: isize, height: isize) -> Self {
-        let mut tiles = Vec::new();
-        let tiles_changed = Rc::new(RefCell::new(Vec::new()));
-
-        for y in 0..height {
-            for x in 0..width {
-                tiles.push(Tile::new(
-                    TileValue::Empty(0),
-                    x,
-                    y,
-                    tiles_changed.clone(),
-                    width,
-                ));
-            }
-        }
-
-        let _vao = draw::generate_game_vao(width, height);
-        let tile_drawer = TileDrawer::new(&tiles);
+        Self::with_rng(width, height, StdRng::from_entropy())
+    }
 
-        _vao.bind();
+    /// Creates a game whose mine placement only depends on `seed` and the
+    /// first revealed tile.
+    pub fn with_seed(width: isize, height: isize, seed: u64) -> Self {
+        Self::with_rng(width, height, StdRng::seed_from_u64(seed))
+    }
 
+    fn with_rng(width: isize, height: isize, rng: StdRng) -> Self {
         Game {
-            tiles,
+            board: Board::new(width, height),
             width,
             height,
             state: GameState::Start,
-            mine_count: width * height / 5,
-            _vao,
-            tile_drawer,
-            tiles_changed,
+            mine_count: mines_for_density(
+                width,
+                height,
+                DEFAULT_MINE_DENSITY,
+                FirstClick::default(),
+            ),
+            rules: Rules::default(),
+            cursor: None,
+            hovered: None,
+            pressed: Vec::new(),
+            loaded: false,
+            mine_density: DEFAULT_MINE_DENSITY,
+            tile_drawer: None,
+            events: EventQueue::default(),
+            rng,
         }
     }
 
+    pub fn set_mine_count(&mut self, mine_count: isize) {
+        let max_mines =
+            self.rules.first_click.max_mines(self.width, self.height);
+
+        self.mine_count = mine_count.clamp(0, max_mines);
+        self.mine_density =
+            self.mine_count as f64 / (self.width * self.height) as f64;
+    }
+
+    /// Sets the share of tiles holding a mine. Unlike a fixed count, the
+    /// density is kept when the board is resized.
+    pub fn set_mine_density(&mut self, mine_density: f64) {
+        self.mine_density = mine_density;
+        self.mine_count = mines_for_density(
+            self.width,
+            self.height,
+            mine_density,
+            self.rules.first_click,
+        );
+    }
+
+    /// Starts a new game on a board of the same size and density. Event
+    /// subscriptions carry over, and a seeded game stays deterministic.
+    pub fn restart(&mut self) {
+        *self = self.resized(self.width, self.height);
+    }
+
+    /// Starts a new game on a board of another size, keeping the rules and
+    /// event subscriptions.
+    pub fn resize(&mut self, width: isize, height: isize) {
+        *self = self.resized(width, height);
+    }
+
+    fn resized(&mut self, width: isize, height: isize) -> Game {
+        let rng = StdRng::seed_from_u64(self.rng.gen());
+        let mut game = Self::with_rng(width, height, rng);
+
+        game.rules = self.rules;
+        game.cursor = self
+            .cursor
+            .map(|(x, y)| (x.min(width - 1), y.min(height - 1)));
+        game.set_mine_density(self.mine_density);
+        game.events = std::mem::take(&mut self.events);
+        game
+    }
+
+    /// Whether the game was opened from a save or other text, rather than
+    /// dealt. The games started after it are dealt.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        self.events.subscribe()
+    }
+
     fn init(&mut self, start_x: isize, start_y: isize) {
         if self.get_tile(start_x, start_y).is_flagged() {
             return;
@@ -77,30 +162,44 @@ impl Game {
 
         self.place_mines(start_x, start_y);
         self.place_numbers();
-        self.state = GameState::Playing(time::Instant::now());
+        self.state = GameState::Playing(Clock::start());
+        self.events.publish(GameEvent::TimerStarted);
     }
 
     fn place_mines(&mut self, start_x: isize, start_y: isize) {
         let mut mines = 0;
-
-        while mines < self.mine_count {
-            let (x, y) = coordinates::random_coords(self.width, self.height);
-
-            let (start_x, start_y) = (
-                if start_x == 0 { 1 } else { start_x },
-                if start_y == 0 { 1 } else { start_y },
+        let mine_count = self
+            .mine_count
+            .min(self.rules.first_click.max_mines(self.width, self.height));
+
+        while mines < mine_count {
+            let (x, y) = coordinates::random_coords(
+                &mut self.rng,
+                self.width,
+                self.height,
             );
 
-            let is_adjacent = x >= start_x - 1
-                && x <= start_x + 1
-                && y >= start_y - 1
-                && y <= start_y + 1;
+            let is_protected = match self.rules.first_click {
+                FirstClick::SafeArea => {
+                    let (start_x, start_y) = (
+                        if start_x == 0 { 1 } else { start_x },
+                        if start_y == 0 { 1 } else { start_y },
+                    );
+
+                    x >= start_x - 1
+                        && x <= start_x + 1
+                        && y >= start_y - 1
+                        && y <= start_y + 1
+                }
+                FirstClick::SafeTile => x == start_x && y == start_y,
+                FirstClick::Unprotected => false,
+            };
 
-            if is_adjacent || self.get_tile(x, y).is_bomb() {
+            if is_protected || self.get_tile(x, y).is_bomb() {
                 continue;
             }
 
-            self.get_tile_mut(x, y).set_value(TileValue::Bomb);
+            self.board.set_value(x, y, TileValue::Bomb);
             mines += 1;
         }
     }
@@ -120,56 +219,75 @@ impl Game {
                     }
                 });
 
-                self.get_tile_mut(x, y).set_value(TileValue::Empty(bombs));
+                self.board.set_value(x, y, TileValue::Empty(bombs));
             }
         }
     }
 
+    /// Reveals a tile, and around it while it's blank. The blank area is
+    /// walked breadth first with a queue of its own, as it can span the
+    /// whole board: tiles are revealed as they're queued, so each is queued
+    /// at most once and the queue only holds the edge of the area.
     fn reveal_tile(&mut self, x: isize, y: isize) {
-        let tile = self.get_tile_mut(x, y);
+        let tile = self.get_tile(x, y);
 
         if tile.is_revealed() || tile.is_flagged() {
             return;
         }
-        match tile.get_value() {
-            TileValue::Bomb => {
-                tile.set_state(TileState::Exploded);
-                self.reveal_all();
-                if let GameState::Playing(start_time) = self.state {
-                    self.state =
-                        GameState::Lost(time::Instant::now() - start_time);
-                }
+        if tile.is_bomb() {
+            self.board.set_state(x, y, TileState::Exploded);
+            self.events.publish(GameEvent::MineExploded { x, y });
+            self.reveal_all();
+            if let GameState::Playing(clock) = self.state {
+                let duration = clock.elapsed();
+                self.state = GameState::Lost(duration);
+                self.events.publish(GameEvent::GameLost(duration));
             }
-            TileValue::Empty(0) => {
-                tile.set_state(TileState::Revealed);
-                self.do_for_adjacent_tiles(x, y, |game, tile| {
-                    game.reveal_tile(tile.x, tile.y);
-                });
+            return;
+        }
+
+        let mut pending = VecDeque::from([(x, y)]);
+        self.board.set_state(x, y, TileState::Revealed);
+        self.events.publish(GameEvent::TileRevealed { x, y });
+
+        while let Some((x, y)) = pending.pop_front() {
+            if self.get_tile(x, y).get_value() != TileValue::Empty(0) {
+                continue;
+            }
+            // Neighbours of blank tiles are never mines.
+            for y in y - 1..=y + 1 {
+                for x in x - 1..=x + 1 {
+                    if self.contains(x, y) && self.get_tile(x, y).is_hidden() {
+                        self.board.set_state(x, y, TileState::Revealed);
+                        self.events.publish(GameEvent::TileRevealed { x, y });
+                        pending.push_back((x, y));
+                    }
+                }
             }
-            _ => tile.set_state(TileState::Revealed),
         }
     }
 
     fn reveal_all(&mut self) {
-        self.tiles
-            .iter_mut()
-            .for_each(|tile| match tile.get_value() {
-                TileValue::Bomb => {
-                    if !tile.is_exploded() && !tile.is_flagged() {
-                        tile.set_state(TileState::Revealed);
-                    }
-                }
-                TileValue::Empty(_) => {
-                    if tile.is_flagged() {
-                        tile.set_state(TileState::WrongFlag);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = self.get_tile(x, y);
+                let state = match tile.get_value() {
+                    TileValue::Bomb if tile.is_hidden() => TileState::Revealed,
+                    TileValue::Empty(_) if tile.is_flagged() => {
+                        TileState::WrongFlag
                     }
-                }
-            });
+                    _ => continue,
+                };
+
+                self.board.set_state(x, y, state);
+                self.events.publish(GameEvent::TileRevealed { x, y });
+            }
+        }
     }
 
     fn is_won(&self) -> bool {
-        self.tiles
-            .iter()
+        self.board
+            .tiles()
             .all(|tile| tile.is_revealed() || tile.is_bomb())
     }
 
@@ -177,24 +295,54 @@ impl Game {
         if !self.is_won() {
             return;
         }
-        if let GameState::Playing(start_time) = self.state {
-            self.state = GameState::Won(time::Instant::now() - start_time);
+        let duration = match self.state {
+            GameState::Playing(clock) => Some(clock.elapsed()),
+            _ => None,
+        };
+        if let Some(duration) = duration {
+            self.state = GameState::Won(duration);
         }
         self.flag_all_mines();
+        if let Some(duration) = duration {
+            self.events.publish(GameEvent::GameWon(duration));
+        }
     }
     fn flag_all_mines(&mut self) {
-        self.tiles
-            .iter_mut()
-            .filter(|tile| tile.is_bomb())
-            .for_each(|tile| tile.set_state(TileState::Flagged));
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let tile = self.get_tile(x, y);
+
+                if !tile.is_bomb() || tile.is_flagged() {
+                    continue;
+                }
+
+                self.board.set_state(x, y, TileState::Flagged);
+                self.events.publish(GameEvent::TileFlagged {
+                    x,
+                    y,
+                    flagged: true,
+                });
+            }
+        }
     }
 
     fn flag_tile(&mut self, x: isize, y: isize) {
-        self.get_tile_mut(x, y).toggle_flag();
+        let state = match self.get_tile(x, y).get_state() {
+            TileState::Unrevealed => TileState::Flagged,
+            TileState::Flagged => TileState::Unrevealed,
+            _ => return,
+        };
+
+        self.board.set_state(x, y, state);
+        self.events.publish(GameEvent::TileFlagged {
+            x,
+            y,
+            flagged: state == TileState::Flagged,
+        });
     }
 
     fn revealed_clicked(&mut self, x: isize, y: isize) {
-        let tile = self.get_tile_mut(x, y);
+        let tile = self.get_tile(x, y);
 
         if !tile.is_revealed() || tile.is_flagged() {
             return;
@@ -239,8 +387,8 @@ impl Game {
                 let x = x + x_offset;
                 let y = y + y_offset;
 
-                if x >= 0 && x < self.width && y >= 0 && y < self.height {
-                    let tile = self.get_tile(x, y).clone();
+                if self.contains(x, y) {
+                    let tile = self.get_tile(x, y);
 
                     f(self, tile);
                 }
@@ -249,26 +397,98 @@ impl Game {
     }
 
     pub fn count_flags(&self) -> isize {
-        self.tiles.iter().filter(|tile| tile.is_flagged()).count() as isize
+        self.board.tiles().filter(|tile| tile.is_flagged()).count() as isize
     }
 
-    pub fn left_click(
-        &mut self,
+    /// The tile under a pixel of the window, which may be off the board.
+    pub fn tile_at(
+        &self,
         x_px: f64,
         y_px: f64,
         window_width: f64,
         window_height: f64,
-    ) {
-        let (x, y) = coordinates::tile_position(
+        camera: &Camera,
+    ) -> (isize, isize) {
+        coordinates::tile_position(
             x_px,
             y_px,
             self.width,
             self.height,
             window_width,
             window_height,
-        );
+            camera,
+        )
+    }
+
+    pub fn left_click(
+        &mut self,
+        x_px: f64,
+        y_px: f64,
+        window_width: f64,
+        window_height: f64,
+        camera: &Camera,
+    ) {
+        let (x, y) =
+            self.tile_at(x_px, y_px, window_width, window_height, camera);
+
+        self.reveal_at(x, y);
+    }
+
+    pub fn right_click(
+        &mut self,
+        x_px: f64,
+        y_px: f64,
+        window_width: f64,
+        window_height: f64,
+        camera: &Camera,
+    ) {
+        let (x, y) =
+            self.tile_at(x_px, y_px, window_width, window_height, camera);
+
+        self.flag_at(x, y);
+    }
+
+    pub fn space_click(
+        &mut self,
+        x_px: f64,
+        y_px: f64,
+        window_width: f64,
+        window_height: f64,
+        camera: &Camera,
+    ) {
+        let (x, y) =
+            self.tile_at(x_px, y_px, window_width, window_height, camera);
+
+        self.chord_or_flag_at(x, y);
+    }
+
+    /// Chords on a revealed tile and toggles the flag on a hidden one.
+    pub fn chord_or_flag_at(&mut self, x: isize, y: isize) {
+        if !self.contains(x, y) {
+            return;
+        }
+
+        match self.get_tile(x, y).get_state() {
+            TileState::Revealed => self.chord_at(x, y),
+            TileState::Unrevealed | TileState::Flagged => self.flag_at(x, y),
+            _ => (),
+        }
+    }
 
-        if x >= self.width || y >= self.height {
+    /// Moves the keyboard cursor, showing it in the middle of the board if
+    /// it was hidden.
+    pub fn move_cursor(&mut self, dx: isize, dy: isize) {
+        self.cursor = Some(match self.cursor {
+            Some((x, y)) => (
+                (x + dx).clamp(0, self.width - 1),
+                (y + dy).clamp(0, self.height - 1),
+            ),
+            None => (self.width / 2, self.height / 2),
+        });
+    }
+
+    pub fn reveal_at(&mut self, x: isize, y: isize) {
+        if !self.contains(x, y) {
             return;
         }
 
@@ -276,9 +496,13 @@ impl Game {
             GameState::Start => {
                 self.init(x, y);
                 self.reveal_tile(x, y);
+                // The first reveal can win outright, on boards without mines.
+                self.check_for_win();
             }
             GameState::Playing(_) => {
-                self.revealed_clicked(x, y);
+                if self.rules.chording == Chording::Click {
+                    self.revealed_clicked(x, y);
+                }
                 self.reveal_tile(x, y);
                 self.check_for_win();
             }
@@ -287,23 +511,8 @@ impl Game {
         }
     }
 
-    pub fn right_click(
-        &mut self,
-        x_px: f64,
-        y_px: f64,
-        window_width: f64,
-        window_height: f64,
-    ) {
-        let (x, y) = coordinates::tile_position(
-            x_px,
-            y_px,
-            self.width,
-            self.height,
-            window_width,
-            window_height,
-        );
-
-        if x >= self.width || y >= self.height {
+    pub fn flag_at(&mut self, x: isize, y: isize) {
+        if !self.contains(x, y) {
             return;
         }
 
@@ -312,106 +521,255 @@ impl Game {
         }
     }
 
-    pub fn space_click(
-        &mut self,
-        x_px: f64,
-        y_px: f64,
-        window_width: f64,
-        window_height: f64,
-    ) {
-        let (x, y) = coordinates::tile_position(
-            x_px,
-            y_px,
-            self.width,
-            self.height,
-            window_width,
-            window_height,
-        );
-
-        if x >= self.width || y >= self.height {
+    pub fn chord_at(&mut self, x: isize, y: isize) {
+        if !self.contains(x, y) {
             return;
         }
 
-        match self.state {
-            GameState::Playing(_) | GameState::Start => (),
-            _ => return,
-        }
-        match self.get_tile(x, y).get_state() {
-            TileState::Revealed => {
-                self.revealed_clicked(x, y);
-                self.check_for_win();
-            }
-            TileState::Unrevealed | TileState::Flagged => self.flag_tile(x, y),
-            _ => (),
+        if matches!(self.state, GameState::Playing(_))
+            && self.rules.chording != Chording::Disabled
+        {
+            self.revealed_clicked(x, y);
+            self.check_for_win();
         }
     }
 
-    pub fn increase_size(&mut self) {
+    pub fn contains(&self, x: isize, y: isize) -> bool {
+        x >= 0 && x < self.width && y >= 0 && y < self.height
+    }
+
+    pub fn neighbours(
+        &self,
+        x: isize,
+        y: isize,
+    ) -> impl Iterator<Item = (isize, isize)> + '_ {
+        (-1..2)
+            .flat_map(move |y_offset| {
+                (-1..2).map(move |x_offset| (x + x_offset, y + y_offset))
+            })
+            .filter(move |&coords| coords != (x, y))
+            .filter(|&(x, y)| self.contains(x, y))
+    }
+
+    pub fn increase_size(
+        &mut self,
+        width_increment: isize,
+        height_increment: isize,
+    ) {
         if matches!(self.state, GameState::Playing(_)) {
             return;
         }
 
-        let width = self.width + WIDTH_INCREMENT;
-        let height = self.height + HEIGHT_INCREMENT;
+        let width = self.width + width_increment;
+        let height = self.height + height_increment;
 
         if width > MAX_WIDTH || height > MAX_HEIGHT {
             return;
         }
 
         info!("Increasing size to {}x{}", width, height);
-        *self = Self::new(width, height);
+        *self = self.resized(width, height);
     }
 
-    pub fn decrease_size(&mut self) {
+    pub fn decrease_size(
+        &mut self,
+        width_increment: isize,
+        height_increment: isize,
+    ) {
         if matches!(self.state, GameState::Playing(_)) {
             return;
         }
 
-        let width = self.width - WIDTH_INCREMENT;
-        let height = self.height - HEIGHT_INCREMENT;
+        let width = self.width - width_increment;
+        let height = self.height - height_increment;
 
         if width < MIN_WIDTH || height < MIN_HEIGHT {
             return;
         }
 
         info!("Decreasing size to {}x{}", width, height);
-        *self = Self::new(width, height);
+        *self = self.resized(width, height);
     }
 
-    pub fn get_time_since_start(&self) -> Option<String> {
+    pub fn mines_left(&self) -> isize {
+        self.mine_count - self.count_flags()
+    }
+
+    /// Stops the clock, until `resume` is called. Only a game in progress
+    /// has a clock to stop.
+    pub fn pause(&mut self) {
+        if let GameState::Playing(clock) = &mut self.state {
+            clock.pause();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let GameState::Playing(clock) = &mut self.state {
+            clock.resume();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, GameState::Playing(clock) if clock.is_paused())
+    }
+
+    /// Time spent on the game so far, or in total once it is over.
+    pub fn elapsed(&self) -> time::Duration {
         match self.state {
-            GameState::Playing(start_time) => {
-                let elapsed = start_time.elapsed();
-                let seconds = elapsed.as_secs();
-                let millis = elapsed.subsec_millis();
+            GameState::Start => time::Duration::ZERO,
+            GameState::Playing(clock) => clock.elapsed(),
+            GameState::Won(duration) | GameState::Lost(duration) => duration,
+        }
+    }
 
-                Some(format!("{}.{:03}", seconds, millis))
-            }
+    pub fn get_time_since_start(&self) -> Option<String> {
+        match self.state {
+            GameState::Playing(_) => Some(format_duration(self.elapsed())),
             _ => None,
         }
     }
 
-    pub fn get_tile(&self, x: isize, y: isize) -> &Tile {
-        &self.tiles[(y * self.width + x) as usize]
+    pub fn status(&self) -> String {
+        match self.state {
+            GameState::Won(game_duration) => format!(
+                "Minesweeper | You won! | You took {} seconds",
+                format_duration(game_duration)
+            ),
+            GameState::Lost(game_duration) => format!(
+                "Minesweeper | You lost! | You took {} seconds",
+                format_duration(game_duration)
+            ),
+            GameState::Playing(_) => format!(
+                "Minesweeper | {} mines left | {} seconds",
+                self.mines_left(),
+                self.get_time_since_start().unwrap()
+            ),
+            GameState::Start => "Minesweeper".to_owned(),
+        }
     }
 
-    pub fn get_tile_mut(&mut self, x: isize, y: isize) -> &mut Tile {
-        &mut self.tiles[(y * self.width + x) as usize]
+    pub fn get_tile(&self, x: isize, y: isize) -> Tile {
+        self.board.get(x, y)
     }
 
-    pub fn draw(&self) {
-        self.tile_drawer
-            .update(&self.tiles, self.tiles_changed.borrow().as_slice());
+    fn highlights(&self) -> Vec<Highlight> {
+        let cursor = self.cursor.map(|tile| (tile, tile_drawer::CURSOR));
+        let hovered = self.hovered.map(|tile| (tile, tile_drawer::HOVERED));
+        let pressed = self
+            .pressed
+            .iter()
+            .map(|&tile| (tile, tile_drawer::PRESSED));
 
-        unsafe {
-            gl::DrawElements(
-                gl::TRIANGLES,
-                (6 * self.width * self.height) as i32,
-                gl::UNSIGNED_INT,
-                std::ptr::null(),
-            );
+        cursor.into_iter().chain(hovered).chain(pressed).collect()
+    }
+
+    /// The tiles a press on (`x`, `y`) would push down: the tile itself if
+    /// it is hidden, or its hidden neighbours when `chord` is set or the
+    /// rules chord on click on a clue. Flagged tiles can't be pressed.
+    pub fn press_preview(
+        &self,
+        x: isize,
+        y: isize,
+        chord: bool,
+    ) -> Vec<(isize, isize)> {
+        if !self.contains(x, y)
+            || !matches!(self.state, GameState::Start | GameState::Playing(_))
+        {
+            return Vec::new();
+        }
+
+        let tile = self.get_tile(x, y);
+        let chord = (chord && self.rules.chording != Chording::Disabled)
+            || (tile.is_revealed() && self.rules.chording == Chording::Click);
+
+        if !chord {
+            return match tile.get_state() {
+                TileState::Unrevealed => vec![(x, y)],
+                _ => Vec::new(),
+            };
+        }
+
+        (x - 1..=x + 1)
+            .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+            .filter(|&(x, y)| {
+                self.contains(x, y)
+                    && self.get_tile(x, y).get_state() == TileState::Unrevealed
+            })
+            .collect()
+    }
+
+    /// Draws the board with the tile shader, `time` being the shader's time
+    /// uniform, which new animations start from. The shader's board size
+    /// uniforms must be set to this game's.
+    pub fn draw(&mut self, time: f32) {
+        let highlights = self.highlights();
+        let tile_drawer = self.tile_drawer.get_or_insert_with(|| {
+            TileDrawer::new(
+                &self.board,
+                self.width,
+                self.height,
+                self.events.subscribe(),
+            )
+        });
+        tile_drawer.update(&self.board, highlights, time);
+        tile_drawer.draw();
+    }
+}
+
+/// How many mines `density` puts on a board `width` by `height` tiles,
+/// before the limit of the first click rule. The count is rounded down, so
+/// the default density gives the fifth of the tiles boards always had.
+pub fn density_mine_count(width: isize, height: isize, density: f64) -> isize {
+    ((width * height) as f64 * density).floor() as isize
+}
+
+fn mines_for_density(
+    width: isize,
+    height: isize,
+    density: f64,
+    first_click: FirstClick,
+) -> isize {
+    density_mine_count(width, height, density)
+        .clamp(0, first_click.max_mines(width, height))
+}
+
+fn format_duration(duration: time::Duration) -> String {
+    format!("{}.{:03}", duration.as_secs(), duration.subsec_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveals_a_blank_board_of_the_largest_size() {
+        let mut game = Game::with_seed(MAX_WIDTH, MAX_HEIGHT, 1);
+        game.set_mine_count(0);
+        game.reveal_at(0, 0);
+
+        assert!(game.board.tiles().all(|tile| tile.is_revealed()));
+    }
+
+    #[test]
+    fn default_density_rounds_down() {
+        assert_eq!(Game::with_seed(3, 1, 1).mine_count, 0);
+
+        for width in 1..=100 {
+            for height in 1..=100 {
+                assert_eq!(
+                    density_mine_count(width, height, DEFAULT_MINE_DENSITY),
+                    width * height / 5
+                );
+            }
         }
+    }
+
+    #[test]
+    fn wins_a_board_without_mines_on_the_first_reveal() {
+        let mut game = Game::with_seed(9, 9, 1);
+        game.set_mine_count(0);
+        game.reveal_at(0, 0);
 
-        *self.tiles_changed.borrow_mut() = Vec::new();
+        assert!(matches!(game.state, GameState::Won(_)));
     }
 }
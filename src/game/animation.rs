@@ -0,0 +1,152 @@
+use crate::graphics::buffer::Buffer;
+
+use super::event::GameEvent;
+
+// Kinds of animation, as bits of the second value of each tile.
+/// Hidden until the animation starts, then grows out of the hidden tile.
+const REVEAL: u32 = 1;
+/// Shrinks then springs back.
+const BOUNCE: u32 = 2;
+/// Flashes, as the shock wave of an explosion passes.
+const FLASH: u32 = 4;
+
+/// Seconds between neighbouring tiles of a cascade.
+const CASCADE_STEP: f32 = 0.02;
+/// Longest a cascade takes to spread, however large the opening is.
+const CASCADE_TIME: f32 = 0.6;
+/// Seconds the shock wave of an explosion takes to move by a tile.
+const RIPPLE_STEP: f32 = 0.03;
+/// Longest the shock wave takes to cross the board.
+const RIPPLE_TIME: f32 = 1.2;
+
+/// When each tile's last animation started and what it is, for
+/// `shaders/tile.fs` to play. Animations are only ever derived from game
+/// events and have no effect on the game itself.
+pub struct Animations {
+    ssbo: Buffer<[f32; 2]>,
+    width: isize,
+    height: isize,
+    /// Start time in seconds, then kind, for each tile.
+    data: Vec<[f32; 2]>,
+}
+
+impl Animations {
+    /// Creates the buffer, bound to binding point 1.
+    pub fn new(width: isize, height: isize) -> Self {
+        let data = vec![[0.0; 2]; (width * height) as usize];
+        let ssbo = Buffer::with_data(
+            gl::SHADER_STORAGE_BUFFER,
+            gl::DYNAMIC_DRAW,
+            &data,
+        );
+        ssbo.bind_base(1);
+
+        Animations {
+            ssbo,
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Starts the animations `events` call for, `time` being the time
+    /// uniform of the tile shader.
+    pub fn play(&mut self, events: &[GameEvent], time: f32) {
+        let revealed: Vec<_> = events
+            .iter()
+            .filter_map(|event| match *event {
+                GameEvent::TileRevealed { x, y } => Some((x, y)),
+                _ => None,
+            })
+            .collect();
+        let explosion = events.iter().find_map(|event| match *event {
+            GameEvent::MineExploded { x, y } => Some((x, y)),
+            _ => None,
+        });
+        let mut changed = Vec::new();
+
+        match explosion {
+            // The shock wave sweeps the whole board, uncovering the mines
+            // as it goes.
+            Some(origin) => {
+                let corners = [
+                    (0, 0),
+                    (self.width - 1, 0),
+                    (0, self.height - 1),
+                    (self.width - 1, self.height - 1),
+                ];
+                let step = step(origin, &corners, RIPPLE_STEP, RIPPLE_TIME);
+
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let delay = distance(origin, (x, y)) * step;
+                        self.set((x, y), time + delay, FLASH);
+                    }
+                }
+                for &tile in revealed.iter().chain(&[origin]) {
+                    let delay = distance(origin, tile) * step;
+                    self.set(tile, time + delay, REVEAL | FLASH);
+                }
+                changed.extend([0, self.data.len() - 1]);
+            }
+            // A cascade spreads from the first tile revealed, the one
+            // clicked.
+            None => {
+                if let Some(&origin) = revealed.first() {
+                    let step =
+                        step(origin, &revealed, CASCADE_STEP, CASCADE_TIME);
+
+                    for &tile in &revealed {
+                        let delay = distance(origin, tile) * step;
+                        changed.push(self.set(tile, time + delay, REVEAL));
+                    }
+                }
+            }
+        }
+
+        for event in events {
+            if let GameEvent::TileFlagged {
+                x,
+                y,
+                flagged: true,
+            } = *event
+            {
+                changed.push(self.set((x, y), time, BOUNCE));
+            }
+        }
+
+        if let (Some(&first), Some(&last)) =
+            (changed.iter().min(), changed.iter().max())
+        {
+            self.ssbo.set_sub_data(first, &self.data[first..=last]);
+        }
+    }
+
+    /// Returns the index of the tile.
+    fn set(&mut self, (x, y): (isize, isize), start: f32, kind: u32) -> usize {
+        let index = (x + y * self.width) as usize;
+
+        self.data[index] = [start, kind as f32];
+        index
+    }
+}
+
+/// Seconds between tiles one apart, for a wave from `origin` to reach all
+/// of `tiles` in at most `max_time`.
+fn step(
+    origin: (isize, isize),
+    tiles: &[(isize, isize)],
+    step: f32,
+    max_time: f32,
+) -> f32 {
+    let spread = tiles
+        .iter()
+        .map(|&tile| distance(origin, tile))
+        .fold(1.0, f32::max);
+
+    step.min(max_time / spread)
+}
+
+fn distance(from: (isize, isize), to: (isize, isize)) -> f32 {
+    (((to.0 - from.0).pow(2) + (to.1 - from.1).pow(2)) as f32).sqrt()
+}
@@ -0,0 +1,142 @@
+use super::tile::{Tile, TileState, TileValue};
+
+// Each cell is packed into a single byte: the low nibble holds the value
+// (0-8 for clues, 9 for a mine) and the high nibble holds the state.
+const VALUE_MASK: u8 = 0x0f;
+const STATE_SHIFT: u8 = 4;
+const BOMB: u8 = 9;
+
+pub struct Board {
+    width: isize,
+    cells: Vec<u8>,
+}
+
+impl Board {
+    pub fn new(width: isize, height: isize) -> Self {
+        let empty = encode(TileValue::Empty(0), TileState::Unrevealed);
+
+        Board {
+            width,
+            cells: vec![empty; (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: isize, y: isize) -> Tile {
+        self.tile_at(self.index(x, y))
+    }
+
+    pub fn set_value(&mut self, x: isize, y: isize, value: TileValue) {
+        let index = self.index(x, y);
+        self.cells[index] = encode(value, decode_state(self.cells[index]));
+    }
+
+    pub fn set_state(&mut self, x: isize, y: isize, state: TileState) {
+        let index = self.index(x, y);
+        self.cells[index] = encode(decode_value(self.cells[index]), state);
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = Tile> + '_ {
+        (0..self.cells.len()).map(|index| self.tile_at(index))
+    }
+
+    /// Where tile (`x`, `y`) is in `tiles`, row by row from the bottom.
+    pub fn index(&self, x: isize, y: isize) -> usize {
+        (y * self.width + x) as usize
+    }
+
+    fn tile_at(&self, index: usize) -> Tile {
+        let cell = self.cells[index];
+        let index = index as isize;
+
+        Tile::new(
+            decode_value(cell),
+            decode_state(cell),
+            index % self.width,
+            index / self.width,
+        )
+    }
+}
+
+fn encode(value: TileValue, state: TileState) -> u8 {
+    let value = match value {
+        TileValue::Bomb => BOMB,
+        TileValue::Empty(n) => n,
+    };
+    let state = match state {
+        TileState::Unrevealed => 0,
+        TileState::Revealed => 1,
+        TileState::Flagged => 2,
+        TileState::Exploded => 3,
+        TileState::WrongFlag => 4,
+    };
+
+    value | state << STATE_SHIFT
+}
+
+fn decode_value(cell: u8) -> TileValue {
+    match cell & VALUE_MASK {
+        BOMB => TileValue::Bomb,
+        n => TileValue::Empty(n),
+    }
+}
+
+fn decode_state(cell: u8) -> TileState {
+    match cell >> STATE_SHIFT {
+        0 => TileState::Unrevealed,
+        1 => TileState::Revealed,
+        2 => TileState::Flagged,
+        3 => TileState::Exploded,
+        _ => TileState::WrongFlag,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STATES: [TileState; 5] = [
+        TileState::Unrevealed,
+        TileState::Revealed,
+        TileState::Flagged,
+        TileState::Exploded,
+        TileState::WrongFlag,
+    ];
+
+    #[test]
+    fn packs_every_value_and_state_in_a_byte() {
+        let values = (0..=8).map(TileValue::Empty).chain([TileValue::Bomb]);
+
+        for value in values {
+            for state in STATES {
+                let cell = encode(value, state);
+
+                assert_eq!(decode_value(cell), value);
+                assert_eq!(decode_state(cell), state);
+            }
+        }
+    }
+
+    #[test]
+    fn sets_values_and_states_independently() {
+        let mut board = Board::new(3, 2);
+
+        board.set_value(2, 1, TileValue::Bomb);
+        board.set_state(2, 1, TileState::Flagged);
+        board.set_value(2, 1, TileValue::Empty(8));
+
+        let tile = board.get(2, 1);
+        assert_eq!(tile.get_value(), TileValue::Empty(8));
+        assert_eq!(tile.get_state(), TileState::Flagged);
+        assert_eq!(board.get(0, 1).get_state(), TileState::Unrevealed);
+    }
+
+    #[test]
+    fn lays_tiles_out_row_by_row() {
+        let board = Board::new(3, 2);
+        let coords: Vec<_> =
+            board.tiles().map(|tile| (tile.x, tile.y)).collect();
+
+        assert_eq!(coords, [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+        assert_eq!(board.index(1, 1), 4);
+    }
+}
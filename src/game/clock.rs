@@ -0,0 +1,91 @@
+use std::time::{Duration, Instant};
+
+/// Time spent playing a game, which stands still while it is paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    /// Time counted up to the last pause.
+    elapsed: Duration,
+    /// When the clock last started, unless it is paused.
+    running_since: Option<Instant>,
+}
+
+impl Clock {
+    /// A clock that starts now.
+    pub fn start() -> Self {
+        Clock {
+            elapsed: Duration::ZERO,
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + since.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.elapsed += since.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        self.running_since.get_or_insert_with(Instant::now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    const STEP: Duration = Duration::from_millis(20);
+
+    #[test]
+    fn counts_while_running() {
+        let clock = Clock::start();
+        thread::sleep(STEP);
+
+        assert!(!clock.is_paused());
+        assert!(clock.elapsed() >= STEP);
+    }
+
+    #[test]
+    fn stands_still_while_paused() {
+        let mut clock = Clock::start();
+        thread::sleep(STEP);
+        clock.pause();
+        let paused_at = clock.elapsed();
+        thread::sleep(STEP);
+
+        assert!(clock.is_paused());
+        assert_eq!(clock.elapsed(), paused_at);
+
+        clock.resume();
+        thread::sleep(STEP);
+
+        assert!(clock.elapsed() >= paused_at + STEP);
+        assert!(clock.elapsed() < paused_at + STEP * 50);
+    }
+
+    #[test]
+    fn pausing_twice_counts_once() {
+        let mut clock = Clock::start();
+        clock.pause();
+        let paused_at = clock.elapsed();
+        thread::sleep(STEP);
+        clock.pause();
+        clock.resume();
+        clock.resume();
+
+        assert!(!clock.is_paused());
+        assert!(clock.elapsed() < paused_at + STEP);
+    }
+}
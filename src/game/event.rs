@@ -0,0 +1,151 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    time,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    TimerStarted,
+    /// The tile's contents became visible. This includes the mines and wrong
+    /// flags shown once a game is lost.
+    TileRevealed {
+        x: isize,
+        y: isize,
+    },
+    TileFlagged {
+        x: isize,
+        y: isize,
+        flagged: bool,
+    },
+    MineExploded {
+        x: isize,
+        y: isize,
+    },
+    GameWon(time::Duration),
+    GameLost(time::Duration),
+}
+
+impl GameEvent {
+    pub fn tile(&self) -> Option<(isize, isize)> {
+        match *self {
+            GameEvent::TileRevealed { x, y }
+            | GameEvent::TileFlagged { x, y, .. }
+            | GameEvent::MineExploded { x, y } => Some((x, y)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EventQueue {
+    subscribers: Vec<Sender<GameEvent>>,
+}
+
+impl EventQueue {
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    pub fn publish(&mut self, event: GameEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    // The mine is at (0, 2), the top left.
+    const SOLUTION: &str = "*100\n1100\n0000\n";
+
+    fn events(receiver: &Receiver<GameEvent>) -> Vec<GameEvent> {
+        receiver.try_iter().collect()
+    }
+
+    #[test]
+    fn publishes_to_every_subscriber() {
+        let mut queue = EventQueue::default();
+        let first = queue.subscribe();
+        let second = queue.subscribe();
+
+        queue.publish(GameEvent::TimerStarted);
+
+        assert_eq!(events(&first), [GameEvent::TimerStarted]);
+        assert_eq!(events(&second), [GameEvent::TimerStarted]);
+    }
+
+    #[test]
+    fn prunes_dropped_receivers() {
+        let mut queue = EventQueue::default();
+        let kept = queue.subscribe();
+        drop(queue.subscribe());
+
+        queue.publish(GameEvent::TimerStarted);
+
+        assert_eq!(queue.subscribers.len(), 1);
+        assert_eq!(events(&kept), [GameEvent::TimerStarted]);
+    }
+
+    #[test]
+    fn reports_flags() {
+        let mut game = Game::from_text(SOLUTION, None).unwrap();
+        let receiver = game.subscribe();
+
+        game.flag_at(0, 2);
+        game.flag_at(0, 2);
+
+        assert_eq!(
+            events(&receiver),
+            [
+                GameEvent::TileFlagged {
+                    x: 0,
+                    y: 2,
+                    flagged: true
+                },
+                GameEvent::TileFlagged {
+                    x: 0,
+                    y: 2,
+                    flagged: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_reveals_and_wins() {
+        let mut game = Game::from_text(SOLUTION, None).unwrap();
+        let receiver = game.subscribe();
+
+        game.reveal_at(3, 0);
+
+        let events = events(&receiver);
+        let revealed = events
+            .iter()
+            .filter(|event| matches!(event, GameEvent::TileRevealed { .. }))
+            .count();
+        assert_eq!(events[0], GameEvent::TileRevealed { x: 3, y: 0 });
+        assert_eq!(revealed, 11);
+        assert!(events.contains(&GameEvent::TileFlagged {
+            x: 0,
+            y: 2,
+            flagged: true
+        }));
+        assert!(matches!(events.last(), Some(GameEvent::GameWon(_))));
+    }
+
+    #[test]
+    fn reports_mines_and_losses() {
+        let mut game = Game::from_text(SOLUTION, None).unwrap();
+        let receiver = game.subscribe();
+
+        game.reveal_at(0, 2);
+
+        let events = events(&receiver);
+        assert_eq!(events[0], GameEvent::MineExploded { x: 0, y: 2 });
+        assert!(matches!(events[..], [_, GameEvent::GameLost(_)]));
+    }
+}
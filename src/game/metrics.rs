@@ -0,0 +1,71 @@
+use super::Game;
+
+impl Game {
+    /// The board's 3BV: the minimum number of clicks needed to clear it
+    /// without flags. Each opening counts once, as does every clue that
+    /// doesn't border one. Only meaningful once the mines are placed.
+    pub fn three_bv(&self) -> usize {
+        let mut marked = vec![false; (self.width * self.height) as usize];
+        let mut count = 0;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if marked[self.board.index(x, y)]
+                    || !self.get_tile(x, y).is_empty()
+                {
+                    continue;
+                }
+
+                count += 1;
+                marked[self.board.index(x, y)] = true;
+
+                let mut opening = vec![(x, y)];
+
+                while let Some((x, y)) = opening.pop() {
+                    for (x, y) in self.neighbours(x, y) {
+                        let index = self.board.index(x, y);
+
+                        if marked[index] {
+                            continue;
+                        }
+
+                        marked[index] = true;
+
+                        if self.get_tile(x, y).is_empty() {
+                            opening.push((x, y));
+                        }
+                    }
+                }
+            }
+        }
+
+        count
+            + self
+                .board
+                .tiles()
+                .filter(|tile| {
+                    !tile.is_bomb() && !marked[self.board.index(tile.x, tile.y)]
+                })
+                .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Game;
+
+    fn three_bv(solution: &str) -> usize {
+        Game::from_text(solution, None).unwrap().three_bv()
+    }
+
+    #[test]
+    fn counts_an_opening_once() {
+        assert_eq!(three_bv("*100\n1100\n0000\n"), 1);
+    }
+
+    #[test]
+    fn counts_clues_away_from_openings() {
+        assert_eq!(three_bv("*1\n11\n"), 3);
+        assert_eq!(three_bv("001*100\n0011100\n"), 3);
+    }
+}
@@ -0,0 +1,41 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FirstClick {
+    /// The first revealed tile and its neighbours never hold a mine.
+    #[default]
+    SafeArea,
+    /// Only the first revealed tile is kept free of mines.
+    SafeTile,
+    /// Mines can be anywhere, including under the first click.
+    Unprotected,
+}
+
+impl FirstClick {
+    /// The most mines a `width` x `height` board can hold while keeping the
+    /// first click as safe as this policy promises.
+    pub fn max_mines(&self, width: isize, height: isize) -> isize {
+        match self {
+            FirstClick::SafeArea => {
+                width * height - width.min(3) * height.min(3)
+            }
+            FirstClick::SafeTile => width * height - 1,
+            FirstClick::Unprotected => width * height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Chording {
+    /// Revealing a satisfied clue reveals its neighbours, as does the chord
+    /// key.
+    #[default]
+    Click,
+    /// Only the chord key reveals around a clue.
+    KeyOnly,
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rules {
+    pub first_click: FirstClick,
+    pub chording: Chording,
+}
@@ -0,0 +1,438 @@
+//! Plain-text representation of a board.
+//!
+//! Rows are written top to bottom, the way the board appears on screen, so
+//! the first line of the text is the row with the highest `y`.
+//!
+//! | Char     | Solution | Player view         |
+//! |----------|----------|---------------------|
+//! | `*`      | mine     | revealed mine       |
+//! | `0`..`8` | clue     | revealed clue       |
+//! | `#`      |          | hidden tile         |
+//! | `F`      |          | flagged tile        |
+//! | `X`      |          | exploded mine       |
+//! | `!`      |          | flag without a mine |
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time,
+};
+
+use crate::{MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH};
+
+use super::{
+    tile::{TileState, TileValue},
+    Clock, Game, GameState,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMode {
+    /// Every tile's value, regardless of what the player has uncovered.
+    Solution,
+    /// Only what the player can currently see.
+    PlayerView,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    SizeMismatch {
+        solution: (isize, isize),
+        view: (isize, isize),
+    },
+    InvalidChar {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+    ClueMismatch {
+        row: usize,
+        column: usize,
+        expected: u8,
+        found: u8,
+    },
+    StateMismatch {
+        row: usize,
+        column: usize,
+        found: char,
+    },
+    /// The board is smaller or larger than the game allows.
+    SizeOutOfRange {
+        width: isize,
+        height: isize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "board is empty"),
+            ParseError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} tiles, expected {}",
+                row + 1,
+                found,
+                expected
+            ),
+            ParseError::SizeMismatch { solution, view } => write!(
+                f,
+                "solution is {}x{} but player view is {}x{}",
+                solution.0, solution.1, view.0, view.1
+            ),
+            ParseError::InvalidChar { row, column, found } => write!(
+                f,
+                "unexpected '{}' at row {}, column {}",
+                found,
+                row + 1,
+                column + 1
+            ),
+            ParseError::ClueMismatch {
+                row,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "clue at row {}, column {} is {} but {} mines are adjacent",
+                row + 1,
+                column + 1,
+                found,
+                expected
+            ),
+            ParseError::StateMismatch { row, column, found } => write!(
+                f,
+                "'{}' at row {}, column {} does not match the solution",
+                found,
+                row + 1,
+                column + 1
+            ),
+            ParseError::SizeOutOfRange { width, height } => write!(
+                f,
+                "board is {}x{}, outside the {}x{} to {}x{} range",
+                width, height, MIN_WIDTH, MIN_HEIGHT, MAX_WIDTH, MAX_HEIGHT
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl Game {
+    pub fn to_text(&self, mode: TextMode) -> String {
+        let mut text =
+            String::with_capacity(((self.width + 1) * self.height) as usize);
+
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let tile = self.get_tile(x, y);
+                let c = match mode {
+                    TextMode::Solution => value_char(tile.get_value()),
+                    TextMode::PlayerView => match tile.get_state() {
+                        TileState::Unrevealed => '#',
+                        TileState::Flagged => 'F',
+                        TileState::Revealed => value_char(tile.get_value()),
+                        TileState::Exploded => 'X',
+                        TileState::WrongFlag => '!',
+                    },
+                };
+                text.push(c);
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// The solution followed by the player view, separated by a blank line.
+    pub fn to_save(&self) -> String {
+        format!(
+            "{}\n{}",
+            self.to_text(TextMode::Solution),
+            self.to_text(TextMode::PlayerView)
+        )
+    }
+
+    /// Where the save action writes the game: `saved_game.txt` in the
+    /// `glsweeper-rs` folder of the data directory.
+    pub fn save_path() -> Option<PathBuf> {
+        dirs::data_dir()
+            .map(|dir| dir.join("glsweeper-rs").join("saved_game.txt"))
+    }
+
+    /// Writes [`Game::to_save`] to `path`, creating its folder.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_save())
+    }
+
+    /// Parses the output of [`Game::to_save`]. The player view may be left
+    /// out, in which case every tile starts hidden.
+    pub fn from_save(text: &str) -> Result<Game, ParseError> {
+        let lines: Vec<&str> = text.trim().lines().collect();
+
+        match lines.iter().position(|line| line.trim().is_empty()) {
+            Some(blank) => Self::from_text(
+                &lines[..blank].join("\n"),
+                Some(&lines[blank + 1..].join("\n")),
+            ),
+            None => Self::from_text(text, None),
+        }
+    }
+
+    /// Rebuilds a game from a solution grid and, optionally, the player's
+    /// view of it. Clues in the solution are checked against the mines.
+    ///
+    /// The game resumes where the view left off: lost if a mine exploded,
+    /// won if every safe tile is revealed and in progress otherwise.
+    pub fn from_text(
+        solution: &str,
+        view: Option<&str>,
+    ) -> Result<Game, ParseError> {
+        let solution = parse_grid(solution)?;
+        let (width, height) = grid_size(&solution);
+        if !(MIN_WIDTH..=MAX_WIDTH).contains(&width)
+            || !(MIN_HEIGHT..=MAX_HEIGHT).contains(&height)
+        {
+            return Err(ParseError::SizeOutOfRange { width, height });
+        }
+        let view = match view {
+            Some(view) => {
+                let view = parse_grid(view)?;
+                if grid_size(&view) != (width, height) {
+                    return Err(ParseError::SizeMismatch {
+                        solution: (width, height),
+                        view: grid_size(&view),
+                    });
+                }
+                Some(view)
+            }
+            None => None,
+        };
+
+        let mut game = Game::new(width, height);
+        game.loaded = true;
+        let mut mine_count = 0;
+
+        for (row, line) in solution.iter().enumerate() {
+            for (column, &c) in line.iter().enumerate() {
+                match c {
+                    '*' => {
+                        let (x, y) = tile_coords(row, column, height);
+                        game.board.set_value(x, y, TileValue::Bomb);
+                        mine_count += 1;
+                    }
+                    '0'..='8' => (),
+                    found => {
+                        return Err(ParseError::InvalidChar {
+                            row,
+                            column,
+                            found,
+                        })
+                    }
+                }
+            }
+        }
+
+        game.mine_count = mine_count;
+        game.mine_density = mine_count as f64 / (width * height) as f64;
+        game.place_numbers();
+
+        for (row, line) in solution.iter().enumerate() {
+            for (column, &c) in line.iter().enumerate() {
+                let (x, y) = tile_coords(row, column, height);
+                if let (Some(found), TileValue::Empty(expected)) =
+                    (c.to_digit(10), game.get_tile(x, y).get_value())
+                {
+                    if found as u8 != expected {
+                        return Err(ParseError::ClueMismatch {
+                            row,
+                            column,
+                            expected,
+                            found: found as u8,
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(view) = view {
+            for (row, line) in view.iter().enumerate() {
+                for (column, &c) in line.iter().enumerate() {
+                    let (x, y) = tile_coords(row, column, height);
+                    let state = match (c, game.get_tile(x, y).get_value()) {
+                        ('#', _) => TileState::Unrevealed,
+                        ('F', _) => TileState::Flagged,
+                        ('X', TileValue::Bomb) => TileState::Exploded,
+                        ('*', TileValue::Bomb) => TileState::Revealed,
+                        ('!', TileValue::Empty(_)) => TileState::WrongFlag,
+                        ('0'..='8', value) if value_char(value) == c => {
+                            TileState::Revealed
+                        }
+                        ('X' | '*' | '!' | '0'..='8', _) => {
+                            return Err(ParseError::StateMismatch {
+                                row,
+                                column,
+                                found: c,
+                            })
+                        }
+                        (found, _) => {
+                            return Err(ParseError::InvalidChar {
+                                row,
+                                column,
+                                found,
+                            })
+                        }
+                    };
+                    game.board.set_state(x, y, state);
+                }
+            }
+        }
+
+        game.state = if game.board.tiles().any(|tile| tile.is_exploded()) {
+            GameState::Lost(time::Duration::ZERO)
+        } else if game.is_won() {
+            GameState::Won(time::Duration::ZERO)
+        } else {
+            GameState::Playing(Clock::start())
+        };
+
+        Ok(game)
+    }
+}
+
+fn value_char(value: TileValue) -> char {
+    match value {
+        TileValue::Bomb => '*',
+        TileValue::Empty(n) => (b'0' + n) as char,
+    }
+}
+
+fn parse_grid(text: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    let grid: Vec<Vec<char>> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.chars().collect())
+        .collect();
+
+    let expected = match grid.first() {
+        Some(row) => row.len(),
+        None => return Err(ParseError::Empty),
+    };
+
+    for (row, line) in grid.iter().enumerate() {
+        if line.len() != expected {
+            return Err(ParseError::RaggedRow {
+                row,
+                expected,
+                found: line.len(),
+            });
+        }
+    }
+
+    Ok(grid)
+}
+
+fn grid_size(grid: &[Vec<char>]) -> (isize, isize) {
+    (grid[0].len() as isize, grid.len() as isize)
+}
+
+fn tile_coords(row: usize, column: usize, height: isize) -> (isize, isize) {
+    (column as isize, height - 1 - row as isize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLUTION: &str = "*100\n1100\n0000\n";
+
+    #[test]
+    fn round_trips_through_text() {
+        let view = "#1##\n1100\n0000\n";
+        let game = Game::from_text(SOLUTION, Some(view)).unwrap();
+
+        assert_eq!((game.width, game.height), (4, 3));
+        assert_eq!(game.mine_count, 1);
+        assert_eq!(game.to_text(TextMode::Solution), SOLUTION);
+        assert_eq!(game.to_text(TextMode::PlayerView), view);
+        assert!(matches!(game.state, GameState::Playing(_)));
+    }
+
+    #[test]
+    fn reports_clue_mismatch() {
+        assert_eq!(
+            Game::from_text("*1\n12\n", None).err(),
+            Some(ParseError::ClueMismatch {
+                row: 1,
+                column: 1,
+                expected: 1,
+                found: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert_eq!(Game::from_text("", None).err(), Some(ParseError::Empty));
+        assert_eq!(
+            Game::from_text("\n  \n", None).err(),
+            Some(ParseError::Empty)
+        );
+    }
+
+    #[test]
+    fn rejects_boards_too_large() {
+        let row = "0".repeat(MAX_WIDTH as usize + 1);
+
+        assert_eq!(
+            Game::from_text(&row, None).err(),
+            Some(ParseError::SizeOutOfRange {
+                width: MAX_WIDTH + 1,
+                height: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn detects_lost_and_won_games() {
+        let lost = Game::from_text(SOLUTION, Some("X1##\n1100\n0000\n"));
+        let won = Game::from_text(SOLUTION, Some("#100\n1100\n0000\n"));
+
+        assert!(matches!(lost.unwrap().state, GameState::Lost(_)));
+        assert!(matches!(won.unwrap().state, GameState::Won(_)));
+    }
+
+    #[test]
+    fn round_trips_through_save() {
+        let mut game = Game::with_seed(9, 9, 1);
+        game.set_mine_count(10);
+        game.reveal_at(4, 4);
+        game.flag_at(0, 0);
+
+        let save = game.to_save();
+        let loaded = Game::from_save(&save).unwrap();
+
+        assert_eq!(loaded.to_save(), save);
+        assert_eq!(loaded.mine_count, 10);
+        assert!(matches!(loaded.state, GameState::Playing(_)));
+    }
+
+    #[test]
+    fn loads_a_solution_alone_as_hidden() {
+        let game = Game::from_save(SOLUTION).unwrap();
+
+        assert_eq!(game.to_text(TextMode::PlayerView), "####\n####\n####\n");
+    }
+}
@@ -1,5 +1,3 @@
-use std::{cell::RefCell, rc::Rc};
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TileValue {
     Bomb,
@@ -15,31 +13,26 @@ pub enum TileState {
     WrongFlag,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Tile {
     pub x: isize,
     pub y: isize,
     tile_value: TileValue,
     tile_state: TileState,
-    tiles_changed: Rc<RefCell<Vec<isize>>>,
-    game_width: isize,
 }
 
 impl Tile {
     pub fn new(
         tile_value: TileValue,
+        tile_state: TileState,
         x: isize,
         y: isize,
-        tiles_changed: Rc<RefCell<Vec<isize>>>,
-        game_width: isize,
     ) -> Self {
         Tile {
             tile_value,
-            tile_state: TileState::Unrevealed,
+            tile_state,
             x,
             y,
-            tiles_changed,
-            game_width,
         }
     }
 
@@ -74,47 +67,4 @@ impl Tile {
     pub fn get_value(&self) -> TileValue {
         self.tile_value
     }
-
-    pub fn set_state(&mut self, state: TileState) {
-        self.tile_state = state;
-        self.changed();
-    }
-
-    pub fn set_value(&mut self, value: TileValue) {
-        self.tile_value = value;
-        self.changed();
-    }
-
-    pub fn toggle_flag(&mut self) {
-        match self.tile_state {
-            TileState::Unrevealed => self.tile_state = TileState::Flagged,
-            TileState::Flagged => self.tile_state = TileState::Unrevealed,
-            _ => (),
-        }
-        self.changed();
-    }
-
-    pub fn unflag(&mut self) {
-        self.tile_state = TileState::Unrevealed;
-        self.changed();
-    }
-
-    fn changed(&mut self) {
-        self.tiles_changed
-            .borrow_mut()
-            .push(self.x + self.y * self.game_width);
-    }
-}
-
-impl Clone for Tile {
-    fn clone(&self) -> Self {
-        Tile {
-            tile_value: self.tile_value,
-            tile_state: self.tile_state,
-            x: self.x,
-            y: self.y,
-            tiles_changed: self.tiles_changed.clone(),
-            game_width: self.game_width,
-        }
-    }
 }
@@ -1,55 +1,148 @@
-use std::time;
+use std::{sync::mpsc::Receiver, time};
 
-use log::info;
+use log::debug;
 
-use crate::graphics::ssbo::SSBO;
+use crate::graphics::{buffer::Buffer, quad::Quad};
 
-use super::tile::{Tile, TileState, TileValue};
+use super::{
+    animation::Animations,
+    board::Board,
+    event::GameEvent,
+    tile::{Tile, TileState, TileValue},
+};
 
+/// Draws the board as a single quad, the tile shader looking up the tile
+/// under each pixel. The only GPU memory per tile is its entries in the
+/// SSBOs.
 pub struct TileDrawer {
-    ssbo: SSBO,
+    quad: Quad,
+    /// `tile_data` of each tile.
+    ssbo: Buffer<[f32; 2]>,
+    animations: Animations,
+    width: isize,
+    events: Receiver<GameEvent>,
+    highlights: Vec<Highlight>,
 }
 
-impl TileDrawer {
-    pub fn new(tiles: &[Tile]) -> Self {
-        let ssbo = SSBO::new();
-        ssbo.bind();
-        ssbo.bind_buffer_base(0);
+/// A tile drawn differently without its state changing, and how: any of
+/// `CURSOR`, `HOVERED` and `PRESSED`.
+pub type Highlight = ((isize, isize), u32);
 
-        let data = tiles.iter().map(get_texture_offset).collect::<Vec<f32>>();
+/// Under the keyboard cursor.
+pub const CURSOR: u32 = 1;
+/// Under the mouse pointer.
+pub const HOVERED: u32 = 2;
+/// Held down by a mouse button, or around a tile being chorded.
+pub const PRESSED: u32 = 4;
 
-        ssbo.bind_buffer_data(&data);
+impl TileDrawer {
+    pub fn new(
+        board: &Board,
+        width: isize,
+        height: isize,
+        events: Receiver<GameEvent>,
+    ) -> Self {
+        let data = board
+            .tiles()
+            .map(|tile| tile_data(tile, &[]))
+            .collect::<Vec<_>>();
+        let ssbo = Buffer::with_data(
+            gl::SHADER_STORAGE_BUFFER,
+            gl::DYNAMIC_DRAW,
+            &data,
+        );
+        ssbo.bind_base(0);
+
+        TileDrawer {
+            quad: Quad::new(),
+            ssbo,
+            animations: Animations::new(width, height),
+            width,
+            events,
+            highlights: Vec::new(),
+        }
+    }
 
-        TileDrawer { ssbo }
+    /// Draws the board's quad with the tile shader in use.
+    pub fn draw(&self) {
+        self.quad.draw();
     }
-    pub fn update(&self, tiles: &[Tile], tiles_changed: &[isize]) {
+
+    /// Uploads the tiles that changed, and starts their animations at
+    /// `time`.
+    pub fn update(
+        &mut self,
+        board: &Board,
+        highlights: Vec<Highlight>,
+        time: f32,
+    ) {
+        let events: Vec<GameEvent> = self.events.try_iter().collect();
+        self.animations.play(&events, time);
+
+        let mut tiles_changed: Vec<isize> = events
+            .iter()
+            .filter_map(|event| event.tile())
+            .map(|(x, y)| x + y * self.width)
+            .collect();
+
+        if highlights != self.highlights {
+            tiles_changed.extend(
+                self.highlights
+                    .iter()
+                    .chain(&highlights)
+                    .map(|((x, y), _)| x + y * self.width),
+            );
+            self.highlights = highlights;
+        }
+
         if tiles_changed.is_empty() {
             return;
         }
+        tiles_changed.sort_unstable();
+        tiles_changed.dedup();
 
-        let start = time::Instant::now();
-        let (first_index_changed, last_index_changed) =
-            tiles_changed.iter().fold(
-                (tiles_changed[0], tiles_changed[0]),
-                |(min, max), &index| (min.min(index), max.max(index)),
-            );
+        // Adjacent tiles go up together, and nothing between tiles apart,
+        // like the cursor and the hovered tile.
+        let mut runs: Vec<(isize, isize)> = Vec::new();
+        for index in tiles_changed {
+            match runs.last_mut() {
+                Some((_, last)) if index == *last + 1 => *last = index,
+                _ => runs.push((index, index)),
+            }
+        }
 
-        let data: Vec<f32> = tiles
-            .iter()
-            .skip(first_index_changed as usize)
-            .take((last_index_changed - first_index_changed + 1) as usize)
-            .map(get_texture_offset)
-            .collect();
+        let start = time::Instant::now();
+        for &(first, last) in &runs {
+            let data: Vec<_> = (first..=last)
+                .map(|index| {
+                    let tile =
+                        board.get(index % self.width, index / self.width);
+                    tile_data(tile, &self.highlights)
+                })
+                .collect();
 
-        self.ssbo.bind_buffer_sub_data(
-            first_index_changed * std::mem::size_of::<f32>() as isize,
-            &data,
+            self.ssbo.set_sub_data(first as usize, &data);
+        }
+        debug!(
+            "SSBO update of {} runs took {:?}",
+            runs.len(),
+            start.elapsed()
         );
-        info!("SSBO update took {:?}, ", start.elapsed());
     }
 }
 
-fn get_texture_offset(tile: &Tile) -> f32 {
+/// What the tile shader reads of a tile: its texture index, then the
+/// highlight bits.
+fn tile_data(tile: Tile, highlights: &[Highlight]) -> [f32; 2] {
+    let highlight = highlights
+        .iter()
+        .filter(|&&(position, _)| position == (tile.x, tile.y))
+        .fold(0, |bits, &(_, highlight)| bits | highlight);
+
+    [get_texture_offset(tile), highlight as f32]
+}
+
+fn get_texture_offset(tile: Tile) -> f32 {
     match tile.get_state() {
         TileState::Unrevealed => 0.0,
         TileState::Flagged => 1.0,
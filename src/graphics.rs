@@ -1,5 +1,18 @@
+pub mod buffer;
+pub mod camera;
+pub mod debug;
+pub mod gamepad;
 pub mod gl_wrapper;
+pub mod hud;
+pub mod input;
+pub mod layout;
+pub mod menu;
+pub mod minimap;
+pub mod overlay;
+pub mod quad;
 pub mod shader;
-pub mod ssbo;
+pub mod shaders;
+pub mod text;
 pub mod texture;
+pub mod theme;
 pub mod window;
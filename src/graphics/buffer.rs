@@ -0,0 +1,312 @@
+use std::{
+    ffi::c_void,
+    marker::PhantomData,
+    mem::size_of,
+    ops::{Deref, DerefMut, Range},
+    ptr, slice,
+};
+
+use bytemuck::Pod;
+use gl::types::{GLbitfield, GLenum, GLsizeiptr, GLuint};
+
+use crate::graphics::debug;
+
+/// How a mapping may be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    /// Writes only. What's read from the mapping is undefined.
+    Write,
+    ReadWrite,
+}
+
+impl Access {
+    fn bits(self) -> GLbitfield {
+        match self {
+            Access::Read => gl::MAP_READ_BIT,
+            Access::Write => gl::MAP_WRITE_BIT,
+            Access::ReadWrite => gl::MAP_READ_BIT | gl::MAP_WRITE_BIT,
+        }
+    }
+}
+
+/// A GL buffer holding elements of `T`, like vertices, indices or the
+/// entries of a shader storage block.
+///
+/// Uploads bind the buffer to its target, where it stays bound. Offsets and
+/// lengths are in elements, not bytes, and empty slices are fine anywhere.
+pub struct Buffer<T: Pod> {
+    id: GLuint,
+    target: GLenum,
+    usage: GLenum,
+    len: usize,
+    _elements: PhantomData<T>,
+}
+
+impl<T: Pod> Buffer<T> {
+    /// An empty buffer for `target`, like `gl::ARRAY_BUFFER`, filled with
+    /// `usage`, like `gl::STATIC_DRAW`.
+    pub fn new(target: GLenum, usage: GLenum) -> Self {
+        let mut id = 0;
+        unsafe {
+            gl::GenBuffers(1, &mut id);
+        }
+        debug::check("Buffer::new");
+
+        Buffer {
+            id,
+            target,
+            usage,
+            len: 0,
+            _elements: PhantomData,
+        }
+    }
+
+    pub fn with_data(target: GLenum, usage: GLenum, data: &[T]) -> Self {
+        let mut buffer = Self::new(target, usage);
+        buffer.set_data(data);
+        buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn bind(&self) {
+        unsafe {
+            gl::BindBuffer(self.target, self.id);
+        }
+    }
+
+    pub fn unbind(&self) {
+        unsafe {
+            gl::BindBuffer(self.target, 0);
+        }
+    }
+
+    /// Binds the buffer to binding point `index` of its target, which must
+    /// be an indexed one like `gl::SHADER_STORAGE_BUFFER`. The binding
+    /// holds through uploads and resizes.
+    pub fn bind_base(&self, index: u32) {
+        unsafe {
+            gl::BindBufferBase(self.target, index, self.id);
+        }
+        debug::check("Buffer::bind_base");
+    }
+
+    /// Replaces the contents with `data`, resizing to fit.
+    pub fn set_data(&mut self, data: &[T]) {
+        self.bind();
+        unsafe {
+            gl::BufferData(
+                self.target,
+                byte_len::<T>(data.len()),
+                data_ptr(data),
+                self.usage,
+            );
+        }
+        self.len = data.len();
+        debug::check("Buffer::set_data");
+    }
+
+    /// Replaces the elements from `offset` with `data`.
+    ///
+    /// # Panics
+    ///
+    /// If `data` doesn't fit in the buffer from `offset`.
+    pub fn set_sub_data(&self, offset: usize, data: &[T]) {
+        assert!(
+            offset + data.len() <= self.len,
+            "Can't write {} elements at {} in a buffer of {}",
+            data.len(),
+            offset,
+            self.len
+        );
+        if data.is_empty() {
+            return;
+        }
+
+        self.bind();
+        unsafe {
+            gl::BufferSubData(
+                self.target,
+                byte_len::<T>(offset),
+                byte_len::<T>(data.len()),
+                data_ptr(data),
+            );
+        }
+        debug::check("Buffer::set_sub_data");
+    }
+
+    /// Resizes to `len` elements, keeping those that fit and zeroing the
+    /// new ones. The buffer keeps its name, so vertex arrays and binding
+    /// points using it don't need to be set up again.
+    pub fn resize(&mut self, len: usize) {
+        if len == self.len {
+            return;
+        }
+        let kept = len.min(self.len);
+
+        unsafe {
+            // The copy targets leave the bindings of the vertex array and
+            // of the other targets alone.
+            let mut copy = 0;
+            if kept > 0 {
+                gl::GenBuffers(1, &mut copy);
+                gl::BindBuffer(gl::COPY_WRITE_BUFFER, copy);
+                gl::BufferData(
+                    gl::COPY_WRITE_BUFFER,
+                    byte_len::<T>(kept),
+                    ptr::null(),
+                    gl::STREAM_COPY,
+                );
+                gl::BindBuffer(gl::COPY_READ_BUFFER, self.id);
+                gl::CopyBufferSubData(
+                    gl::COPY_READ_BUFFER,
+                    gl::COPY_WRITE_BUFFER,
+                    0,
+                    0,
+                    byte_len::<T>(kept),
+                );
+            }
+
+            gl::BindBuffer(gl::COPY_WRITE_BUFFER, self.id);
+            gl::BufferData(
+                gl::COPY_WRITE_BUFFER,
+                byte_len::<T>(len),
+                ptr::null(),
+                self.usage,
+            );
+
+            if kept > 0 {
+                gl::BindBuffer(gl::COPY_READ_BUFFER, copy);
+                gl::CopyBufferSubData(
+                    gl::COPY_READ_BUFFER,
+                    gl::COPY_WRITE_BUFFER,
+                    0,
+                    0,
+                    byte_len::<T>(kept),
+                );
+                gl::DeleteBuffers(1, &copy);
+            }
+            if len > kept {
+                let zeroes = vec![T::zeroed(); len - kept];
+                gl::BufferSubData(
+                    gl::COPY_WRITE_BUFFER,
+                    byte_len::<T>(kept),
+                    byte_len::<T>(zeroes.len()),
+                    data_ptr(&zeroes),
+                );
+            }
+        }
+        self.len = len;
+        debug::check("Buffer::resize");
+    }
+
+    /// Maps the elements in `range` into memory, until the mapping is
+    /// dropped. Returns `None` if the driver can't map them.
+    ///
+    /// # Panics
+    ///
+    /// If `range` goes past the end of the buffer.
+    pub fn map(
+        &mut self,
+        range: Range<usize>,
+        access: Access,
+    ) -> Option<Mapping<'_, T>> {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "Can't map {:?} in a buffer of {}",
+            range,
+            self.len
+        );
+        // Mapping nothing is an error in GL.
+        if range.is_empty() {
+            return Some(Mapping {
+                buffer: self,
+                elements: ptr::NonNull::dangling().as_ptr(),
+                len: 0,
+            });
+        }
+
+        self.bind();
+        let elements = unsafe {
+            gl::MapBufferRange(
+                self.target,
+                byte_len::<T>(range.start),
+                byte_len::<T>(range.len()),
+                access.bits(),
+            )
+        } as *mut T;
+        debug::check("Buffer::map");
+
+        if elements.is_null() {
+            return None;
+        }
+        Some(Mapping {
+            buffer: self,
+            elements,
+            len: range.len(),
+        })
+    }
+}
+
+impl<T: Pod> Drop for Buffer<T> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.id);
+        }
+    }
+}
+
+/// Elements of a buffer mapped into memory, unmapped when dropped.
+pub struct Mapping<'a, T: Pod> {
+    buffer: &'a Buffer<T>,
+    elements: *mut T,
+    len: usize,
+}
+
+impl<T: Pod> Deref for Mapping<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.elements, self.len) }
+    }
+}
+
+impl<T: Pod> DerefMut for Mapping<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.elements, self.len) }
+    }
+}
+
+impl<T: Pod> Drop for Mapping<'_, T> {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        self.buffer.bind();
+        unsafe {
+            gl::UnmapBuffer(self.buffer.target);
+        }
+        debug::check("Mapping::drop");
+    }
+}
+
+fn byte_len<T>(len: usize) -> GLsizeiptr {
+    (len * size_of::<T>()) as GLsizeiptr
+}
+
+/// GL takes null for no data, where an empty slice's pointer dangles.
+fn data_ptr<T>(data: &[T]) -> *const c_void {
+    if data.is_empty() {
+        ptr::null()
+    } else {
+        data.as_ptr() as *const c_void
+    }
+}
@@ -0,0 +1,156 @@
+use crate::graphics::layout::Layout;
+
+/// The part of the board shown in the viewport.
+///
+/// The board spans -1 to 1 on both axes, as laid out by `shaders/tile.vs`,
+/// and the camera maps the area around `center` that is `2 / zoom` across
+/// onto the viewport. The viewport has the board's aspect ratio, so tiles
+/// stay square at any zoom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    zoom: f64,
+    center: (f64, f64),
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            zoom: 1.0,
+            center: (0.0, 0.0),
+        }
+    }
+}
+
+/// How many tiles still fit across the viewport at the highest zoom.
+const MIN_TILES_SHOWN: f64 = 4.0;
+
+impl Camera {
+    /// Shows the whole board.
+    pub fn fit(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The view matrix for the tile shader, in column-major order.
+    pub fn view(&self) -> [f32; 16] {
+        let zoom = self.zoom as f32;
+        let (x, y) = (self.center.0 as f32, self.center.1 as f32);
+
+        #[rustfmt::skip]
+        let view = [
+            zoom, 0.0, 0.0, 0.0,
+            0.0, zoom, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            -x * zoom, -y * zoom, 0.0, 1.0,
+        ];
+
+        view
+    }
+
+    /// The board position drawn at `point`, in normalized device
+    /// coordinates.
+    pub fn to_board(&self, point: (f64, f64)) -> (f64, f64) {
+        (
+            self.center.0 + point.0 / self.zoom,
+            self.center.1 + point.1 / self.zoom,
+        )
+    }
+
+    /// Zooms by `factor`, keeping the board position under `point` in place.
+    pub fn zoom_at(
+        &mut self,
+        factor: f64,
+        point: (f64, f64),
+        width: isize,
+        height: isize,
+    ) {
+        let anchor = self.to_board(point);
+        let max_zoom = (width.max(height) as f64 / MIN_TILES_SHOWN).max(1.0);
+
+        self.zoom = (self.zoom * factor).clamp(1.0, max_zoom);
+        self.center = (
+            anchor.0 - point.0 / self.zoom,
+            anchor.1 - point.1 / self.zoom,
+        );
+        self.clamp();
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    /// The board area in view: left, bottom, right and top.
+    pub fn visible_area(&self) -> [f64; 4] {
+        let half_size = 1.0 / self.zoom;
+
+        [
+            self.center.0 - half_size,
+            self.center.1 - half_size,
+            self.center.0 + half_size,
+            self.center.1 + half_size,
+        ]
+    }
+
+    /// Centres the view on a board position.
+    pub fn look_at(&mut self, position: (f64, f64)) {
+        self.center = position;
+        self.clamp();
+    }
+
+    /// Moves the view by `offset`, in normalized device coordinates.
+    pub fn pan(&mut self, offset: (f64, f64)) {
+        self.center.0 -= offset.0 / self.zoom;
+        self.center.1 -= offset.1 / self.zoom;
+        self.clamp();
+    }
+
+    /// Pans just enough for tile (`x`, `y`) to be in view.
+    pub fn show_tile(
+        &mut self,
+        x: isize,
+        y: isize,
+        width: isize,
+        height: isize,
+    ) {
+        let (left, right) = tile_span(x, width);
+        let (bottom, top) = tile_span(y, height);
+        let half_size = 1.0 / self.zoom;
+
+        self.center.0 =
+            self.center.0.max(right - half_size).min(left + half_size);
+        self.center.1 =
+            self.center.1.max(top - half_size).min(bottom + half_size);
+        self.clamp();
+    }
+
+    fn clamp(&mut self) {
+        let limit = 1.0 - 1.0 / self.zoom;
+
+        self.center = (
+            self.center.0.clamp(-limit, limit),
+            self.center.1.clamp(-limit, limit),
+        );
+    }
+}
+
+fn tile_span(index: isize, tiles: isize) -> (f64, f64) {
+    let size = 2.0 / tiles as f64;
+    let start = index as f64 * size - 1.0;
+
+    (start, start + size)
+}
+
+/// Converts a position in the window, in pixels from the top left, to
+/// normalized device coordinates in the viewport of a board `width` by
+/// `height` tiles.
+pub fn viewport_position(
+    x_px: f64,
+    y_px: f64,
+    window_width: f64,
+    window_height: f64,
+    width: isize,
+    height: isize,
+) -> (f64, f64) {
+    Layout::new(window_width, window_height, width, height)
+        .board
+        .to_ndc(x_px, y_px, window_height)
+}
@@ -0,0 +1,175 @@
+use std::{
+    ffi::{c_void, CStr},
+    slice,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use clap::ValueEnum;
+use gl::types::{GLchar, GLenum, GLsizei, GLuint};
+use log::{error, info, warn, Level};
+
+/// Whether `check` looks for errors, which costs a round trip to the driver
+/// each time.
+static CHECK_CALLS: AtomicBool = AtomicBool::new(false);
+
+/// How GL problems are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DebugMode {
+    /// Ask for a debug context and log the driver's debug messages, through
+    /// KHR_debug
+    Output,
+    /// Look for errors after the calls of each wrapper, for drivers without
+    /// KHR_debug
+    Check,
+    /// Both
+    All,
+}
+
+impl DebugMode {
+    /// Whether the window needs a debug context.
+    pub fn wants_context(self) -> bool {
+        matches!(self, DebugMode::Output | DebugMode::All)
+    }
+}
+
+/// Sets up `mode` on the current context, once GL is loaded. Debug output
+/// falls back to checking calls when the driver can't provide it.
+pub fn init(mode: DebugMode) {
+    let mut check_calls = matches!(mode, DebugMode::Check | DebugMode::All);
+
+    if mode.wants_context() {
+        if has_khr_debug() {
+            let mut flags = 0;
+            unsafe {
+                gl::GetIntegerv(gl::CONTEXT_FLAGS, &mut flags);
+                gl::Enable(gl::DEBUG_OUTPUT);
+                // Messages come from the call that caused them, so the log
+                // shows them in order.
+                gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
+                gl::DebugMessageCallback(Some(log_message), std::ptr::null());
+            }
+            if flags as GLenum & gl::CONTEXT_FLAG_DEBUG_BIT == 0 {
+                info!("GL debug output on, without a debug context");
+            } else {
+                info!("GL debug output on");
+            }
+        } else {
+            warn!("KHR_debug isn't supported, checking GL calls instead");
+            check_calls = true;
+        }
+    }
+
+    CHECK_CALLS.store(check_calls, Ordering::Relaxed);
+}
+
+/// Logs the errors raised since the last check, if calls are checked.
+/// `call` tells where they come from.
+pub fn check(call: &str) {
+    if !CHECK_CALLS.load(Ordering::Relaxed) {
+        return;
+    }
+
+    loop {
+        let error = unsafe { gl::GetError() };
+        if error == gl::NO_ERROR {
+            break;
+        }
+        error!("GL error {} in {}", error_name(error), call);
+    }
+}
+
+fn has_khr_debug() -> bool {
+    let (mut major, mut minor, mut count) = (0, 0, 0);
+    unsafe {
+        gl::GetIntegerv(gl::MAJOR_VERSION, &mut major);
+        gl::GetIntegerv(gl::MINOR_VERSION, &mut minor);
+        gl::GetIntegerv(gl::NUM_EXTENSIONS, &mut count);
+    }
+    if !gl::DebugMessageCallback::is_loaded() {
+        return false;
+    }
+    if (major, minor) >= (4, 3) {
+        return true;
+    }
+
+    (0..count as GLuint).any(|index| unsafe {
+        let name = gl::GetStringi(gl::EXTENSIONS, index);
+        !name.is_null()
+            && CStr::from_ptr(name as *const _).to_bytes() == b"GL_KHR_debug"
+    })
+}
+
+extern "system" fn log_message(
+    source: GLenum,
+    kind: GLenum,
+    id: GLuint,
+    severity: GLenum,
+    length: GLsizei,
+    message: *const GLchar,
+    _user: *mut c_void,
+) {
+    if message.is_null() {
+        return;
+    }
+    let message = unsafe {
+        match length {
+            length if length >= 0 => {
+                slice::from_raw_parts(message as *const u8, length as usize)
+            }
+            _ => CStr::from_ptr(message).to_bytes(),
+        }
+    };
+    let level = match severity {
+        gl::DEBUG_SEVERITY_HIGH => Level::Error,
+        gl::DEBUG_SEVERITY_MEDIUM => Level::Warn,
+        gl::DEBUG_SEVERITY_LOW => Level::Info,
+        _ => Level::Debug,
+    };
+
+    log::log!(
+        level,
+        "GL {} {} {}: {}",
+        source_name(source),
+        kind_name(kind),
+        id,
+        String::from_utf8_lossy(message).trim_end()
+    );
+}
+
+fn source_name(source: GLenum) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "API",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    }
+}
+
+fn kind_name(kind: GLenum) -> &'static str {
+    match kind {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behaviour",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behaviour",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        _ => "message",
+    }
+}
+
+fn error_name(error: GLenum) -> String {
+    match error {
+        gl::INVALID_ENUM => "INVALID_ENUM".to_owned(),
+        gl::INVALID_VALUE => "INVALID_VALUE".to_owned(),
+        gl::INVALID_OPERATION => "INVALID_OPERATION".to_owned(),
+        gl::INVALID_FRAMEBUFFER_OPERATION => {
+            "INVALID_FRAMEBUFFER_OPERATION".to_owned()
+        }
+        gl::OUT_OF_MEMORY => "OUT_OF_MEMORY".to_owned(),
+        gl::STACK_UNDERFLOW => "STACK_UNDERFLOW".to_owned(),
+        gl::STACK_OVERFLOW => "STACK_OVERFLOW".to_owned(),
+        error => format!("{:#x}", error),
+    }
+}
@@ -0,0 +1,145 @@
+use glfw::{GamepadAxis, GamepadButton, JoystickId};
+use log::info;
+
+use crate::{config::Config, graphics::input::Input};
+
+const BUTTONS: [GamepadButton; 11] = [
+    GamepadButton::ButtonA,
+    GamepadButton::ButtonB,
+    GamepadButton::ButtonX,
+    GamepadButton::ButtonY,
+    GamepadButton::ButtonLeftBumper,
+    GamepadButton::ButtonRightBumper,
+    GamepadButton::ButtonBack,
+    GamepadButton::ButtonStart,
+    GamepadButton::ButtonGuide,
+    GamepadButton::ButtonLeftThumb,
+    GamepadButton::ButtonRightThumb,
+];
+
+const DIRECTIONS: [GamepadButton; 4] = [
+    GamepadButton::ButtonDpadUp,
+    GamepadButton::ButtonDpadDown,
+    GamepadButton::ButtonDpadLeft,
+    GamepadButton::ButtonDpadRight,
+];
+
+const JOYSTICKS: [JoystickId; 16] = [
+    JoystickId::Joystick1,
+    JoystickId::Joystick2,
+    JoystickId::Joystick3,
+    JoystickId::Joystick4,
+    JoystickId::Joystick5,
+    JoystickId::Joystick6,
+    JoystickId::Joystick7,
+    JoystickId::Joystick8,
+    JoystickId::Joystick9,
+    JoystickId::Joystick10,
+    JoystickId::Joystick11,
+    JoystickId::Joystick12,
+    JoystickId::Joystick13,
+    JoystickId::Joystick14,
+    JoystickId::Joystick15,
+    JoystickId::Joystick16,
+];
+
+/// Turns the state of the first connected gamepad into inputs. Buttons
+/// trigger once when pressed, while the d-pad and the left stick act as
+/// four direction buttons that repeat as long as they are held.
+pub struct Gamepad {
+    deadzone: f32,
+    repeat_delay: f64,
+    repeat_interval: f64,
+    joystick: Option<JoystickId>,
+    pressed: [bool; BUTTONS.len()],
+    /// When each held direction next repeats.
+    repeats: [Option<f64>; DIRECTIONS.len()],
+}
+
+impl Gamepad {
+    pub fn new(config: &Config) -> Self {
+        Gamepad {
+            deadzone: config.gamepad_deadzone,
+            repeat_delay: config.gamepad_repeat_delay,
+            repeat_interval: config.gamepad_repeat_interval,
+            joystick: None,
+            pressed: [false; BUTTONS.len()],
+            repeats: [None; DIRECTIONS.len()],
+        }
+    }
+
+    /// Returns the inputs triggered since the last poll, `time` being the
+    /// GLFW time in seconds.
+    pub fn poll(&mut self, glfw: &glfw::Glfw, time: f64) -> Vec<Input> {
+        let joystick = JOYSTICKS
+            .into_iter()
+            .map(|id| glfw.get_joystick(id))
+            .find(|joystick| joystick.is_gamepad());
+
+        if self.joystick != joystick.as_ref().map(|joystick| joystick.id) {
+            match &joystick {
+                Some(joystick) => info!(
+                    "Using gamepad {}",
+                    joystick.get_gamepad_name().unwrap_or_default()
+                ),
+                None => info!("Gamepad disconnected"),
+            }
+            self.joystick = joystick.as_ref().map(|joystick| joystick.id);
+        }
+
+        let state =
+            match joystick.and_then(|joystick| joystick.get_gamepad_state()) {
+                Some(state) => state,
+                None => {
+                    self.pressed = [false; BUTTONS.len()];
+                    self.repeats = [None; DIRECTIONS.len()];
+                    return Vec::new();
+                }
+            };
+
+        let is_down =
+            |button| state.get_button_state(button) != glfw::Action::Release;
+        let mut inputs = Vec::new();
+
+        for (button, was_pressed) in BUTTONS.into_iter().zip(&mut self.pressed)
+        {
+            let pressed = is_down(button);
+
+            if pressed && !*was_pressed {
+                inputs.push(Input::Gamepad(button));
+            }
+            *was_pressed = pressed;
+        }
+
+        // The stick's y axis points down.
+        let x = state.get_axis(GamepadAxis::AxisLeftX);
+        let y = state.get_axis(GamepadAxis::AxisLeftY);
+        let stick = [
+            y < -self.deadzone,
+            y > self.deadzone,
+            x < -self.deadzone,
+            x > self.deadzone,
+        ];
+
+        for ((direction, stick), repeat) in
+            DIRECTIONS.into_iter().zip(stick).zip(&mut self.repeats)
+        {
+            if !is_down(direction) && !stick {
+                *repeat = None;
+                continue;
+            }
+
+            match repeat {
+                None => *repeat = Some(time + self.repeat_delay),
+                Some(next) if time >= *next => {
+                    *repeat = Some(time + self.repeat_interval)
+                }
+                Some(_) => continue,
+            }
+
+            inputs.push(Input::Gamepad(direction));
+        }
+
+        inputs
+    }
+}
@@ -1,6 +1,9 @@
-use std::ffi::c_void;
+use std::mem::size_of;
 
-use gl::types::{GLboolean, GLenum, GLfloat, GLsizei};
+use bytemuck::Pod;
+use gl::types::{GLenum, GLsizei};
+
+use crate::graphics::{buffer::Buffer, debug};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VAO {
@@ -14,6 +17,7 @@ impl VAO {
         unsafe {
             gl::GenVertexArrays(1, &mut id);
         }
+        debug::check("VAO::new");
         // info!("VAO created. (id: {})", id);
         VAO { id }
     }
@@ -22,6 +26,7 @@ impl VAO {
         unsafe {
             gl::BindVertexArray(self.id);
         }
+        debug::check("VAO::bind");
     }
 
     pub fn unbind(&self) {
@@ -29,157 +34,107 @@ impl VAO {
             gl::BindVertexArray(0);
         }
     }
-}
-
-impl Drop for VAO {
-    fn drop(&mut self) {
-        unsafe {
-            gl::DeleteVertexArrays(1, &self.id);
-        }
-        // info!("VAO dropped. (id: {})", self.id);
-    }
-}
 
-pub struct VBO {
-    id: gl::types::GLuint,
-    r#type: gl::types::GLenum,
-    usage: gl::types::GLenum,
-}
+    /// Reads the attributes of `V` from `buffer`, leaving the vertex array
+    /// bound.
+    pub fn attach<V: Vertex>(&self, buffer: &Buffer<V>) {
+        self.bind();
+        buffer.bind();
 
-impl VBO {
-    pub fn new(r#type: gl::types::GLenum, usage: gl::types::GLenum) -> Self {
-        let mut id = 0;
-        unsafe {
-            gl::GenBuffers(1, &mut id);
-        }
-        VBO { id, r#type, usage }
-    }
-
-    pub fn bind(&self) {
-        unsafe {
-            gl::BindBuffer(self.r#type, self.id);
-        }
-    }
-
-    pub fn unbind(&self) {
-        unsafe {
-            gl::BindBuffer(self.r#type, 0);
-        }
-    }
-
-    pub fn bind_buffer_data(&self, data: &[f32]) {
-        unsafe {
-            gl::BufferData(
-                self.r#type,
-                (data.len() * std::mem::size_of::<GLfloat>())
-                    as gl::types::GLsizeiptr,
-                &data[0] as *const f32 as *const c_void,
-                self.usage,
-            );
+        for attribute in V::attributes() {
+            unsafe {
+                gl::VertexAttribPointer(
+                    attribute.location,
+                    attribute.components,
+                    attribute.kind,
+                    gl::FALSE,
+                    size_of::<V>() as GLsizei,
+                    attribute.offset as *const _,
+                );
+                gl::EnableVertexAttribArray(attribute.location);
+            }
         }
+        debug::check("VAO::attach");
     }
 }
 
-impl Drop for VBO {
+impl Drop for VAO {
     fn drop(&mut self) {
         unsafe {
-            gl::DeleteBuffers(1, &self.id);
+            gl::DeleteVertexArrays(1, &self.id);
         }
-        // info!("VBO dropped. (id: {})", self.id);
+        // info!("VAO dropped. (id: {})", self.id);
     }
 }
 
-pub struct EBO {
-    id: gl::types::GLuint,
-    r#type: gl::types::GLenum,
-    usage: gl::types::GLenum,
+/// A vertex type, read by shaders as the attributes it lists.
+///
+/// ```ignore
+/// #[repr(C)]
+/// #[derive(Clone, Copy, Pod, Zeroable)]
+/// struct Point {
+///     position: [f32; 2],
+///     size: f32,
+/// }
+///
+/// impl Vertex for Point {
+///     fn attributes() -> Vec<Attribute> {
+///         vec![
+///             Attribute::of(0, |point: &Self| &point.position),
+///             Attribute::of(1, |point: &Self| &point.size),
+///         ]
+///     }
+/// }
+/// ```
+pub trait Vertex: Pod {
+    fn attributes() -> Vec<Attribute>;
 }
 
-impl EBO {
-    pub fn new(r#type: gl::types::GLenum, usage: gl::types::GLenum) -> Self {
-        let mut id = 0;
-        unsafe {
-            gl::GenBuffers(1, &mut id);
-        }
-        EBO { id, r#type, usage }
-    }
-
-    pub fn bind(&self) {
-        unsafe {
-            gl::BindBuffer(self.r#type, self.id);
-        }
-    }
-
-    pub fn unbind(&self) {
-        unsafe {
-            gl::BindBuffer(self.r#type, 0);
-        }
-    }
-
-    pub fn bind_buffer_data(&self, data: &[u32]) {
-        unsafe {
-            gl::BufferData(
-                self.r#type,
-                (data.len() * std::mem::size_of::<u32>())
-                    as gl::types::GLsizeiptr,
-                data.as_ptr() as *const gl::types::GLvoid,
-                self.usage,
-            );
-        }
-    }
+/// Where a vertex attribute is in its vertex, and what it's made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attribute {
+    pub location: u32,
+    pub components: i32,
+    pub kind: GLenum,
+    /// Bytes from the start of the vertex.
+    pub offset: usize,
 }
 
-impl Drop for EBO {
-    fn drop(&mut self) {
-        unsafe {
-            gl::DeleteBuffers(1, &self.id);
+impl Attribute {
+    /// The attribute at `location` read from the field `field` returns.
+    pub fn of<V: Pod, F: AttributeType>(
+        location: u32,
+        field: fn(&V) -> &F,
+    ) -> Self {
+        let vertex = V::zeroed();
+        let start = &vertex as *const V as usize;
+        let offset = field(&vertex) as *const F as usize - start;
+        assert!(offset + size_of::<F>() <= size_of::<V>());
+
+        Attribute {
+            location,
+            components: F::COMPONENTS,
+            kind: F::KIND,
+            offset,
         }
-        // info!("EBO dropped. (id: {})", self.id);
     }
 }
 
-pub struct VertexAttribute {
-    pub index: gl::types::GLuint,
+/// A field type that can be a vertex attribute.
+pub trait AttributeType: Pod {
+    const COMPONENTS: i32;
+    const KIND: GLenum;
 }
 
-impl VertexAttribute {
-    /// # Safety
-    ///
-    /// `offset` must be a offset depending on the stride of the
-    /// VBO and the size of the other attributes.
-    pub unsafe fn new(
-        index: u32,
-        size: i32,
-        r#type: GLenum,
-        normalized: GLboolean,
-        stride: GLsizei,
-        offset: *const c_void,
-    ) -> Self {
-        gl::VertexAttribPointer(
-            index, size, r#type, normalized, stride, offset,
-        );
-
-        VertexAttribute { index }
-    }
-
-    pub fn enable(&self) {
-        unsafe {
-            gl::EnableVertexAttribArray(self.index);
-        }
-    }
-
-    pub fn disable(&self) {
-        unsafe {
-            gl::DisableVertexAttribArray(self.index);
-        }
-    }
+impl AttributeType for f32 {
+    const COMPONENTS: i32 = 1;
+    const KIND: GLenum = gl::FLOAT;
 }
 
-impl Drop for VertexAttribute {
-    fn drop(&mut self) {
-        unsafe {
-            gl::DisableVertexAttribArray(self.index);
-        }
-        // info!("Dropping VertexAttribute: {}", self.index);
-    }
+impl<const N: usize> AttributeType for [f32; N]
+where
+    [f32; N]: Pod,
+{
+    const COMPONENTS: i32 = N as i32;
+    const KIND: GLenum = gl::FLOAT;
 }
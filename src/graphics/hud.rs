@@ -0,0 +1,54 @@
+use crate::{
+    game::{Game, GameState},
+    graphics::{layout::Rect, quad::Quad, shader::Shader},
+};
+
+/// Radius of the face button, relative to the height of the bar. The face
+/// sits in the middle of the bar.
+pub const FACE_RADIUS: f64 = 0.4;
+
+/// The bar above the board: mines left on the left, the restart face in
+/// the middle and the timer on the right, all drawn by `shaders/hud.fs`.
+pub struct Hud {
+    quad: Quad,
+}
+
+#[allow(clippy::new_without_default)]
+impl Hud {
+    pub fn new() -> Self {
+        Hud { quad: Quad::new() }
+    }
+
+    pub fn draw(&self, shader: &Shader, game: &Game, area: &Rect) {
+        let face = match game.state {
+            GameState::Start | GameState::Playing(_) => 0,
+            GameState::Won(_) => 1,
+            GameState::Lost(_) => 2,
+        };
+
+        shader.use_program();
+        shader.set_float("aspect", (area.width / area.height) as f32);
+        shader.set_float("faceRadius", FACE_RADIUS as f32);
+        shader.set_int("minesLeft", game.mines_left().clamp(-99, 999) as i32);
+        shader.set_int("seconds", game.elapsed().as_secs().min(999) as i32);
+        shader.set_int("face", face);
+
+        self.quad.draw();
+    }
+}
+
+/// Whether a window position, in pixels from the top left, is on the face
+/// button of the bar drawn in `area`.
+pub fn face_contains(
+    area: &Rect,
+    x_px: f64,
+    y_px: f64,
+    window_height: f64,
+) -> bool {
+    let (x, y) = area.to_ndc(x_px, y_px, window_height);
+    // In units of the bar's height, from its centre.
+    let x = x * area.width / area.height / 2.0;
+    let y = y / 2.0;
+
+    x.abs() <= FACE_RADIUS && y.abs() <= FACE_RADIUS
+}
@@ -0,0 +1,404 @@
+use glfw::{GamepadButton, Key, MouseButton};
+
+/// Something the player can do from the window, whatever input triggered
+/// it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Reveals the tile under the cursor, or chords on a clue when the
+    /// rules allow it.
+    Reveal,
+    Flag,
+    /// Reveals around the clue under the cursor, or flags a hidden tile.
+    Chord,
+    /// Moves the keyboard cursor, switching to keyboard play: until the
+    /// mouse moves, key bindings act on the cursor instead of the pointer.
+    CursorUp,
+    CursorDown,
+    CursorLeft,
+    CursorRight,
+    /// Drags the view while the mouse button is held.
+    Pan,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    ZoomIn,
+    ZoomOut,
+    /// Zooms out to show the whole board.
+    FitBoard,
+    Restart,
+    IncreaseSize,
+    DecreaseSize,
+    ToggleWireframe,
+    /// Writes the game to the save file, to open again later.
+    Save,
+    /// Stops the clock and hides the board, or resumes the game.
+    Pause,
+    /// Opens the menu, which pauses the game, or goes back out of it.
+    Menu,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Action::Reveal,
+        Action::Flag,
+        Action::Chord,
+        Action::CursorUp,
+        Action::CursorDown,
+        Action::CursorLeft,
+        Action::CursorRight,
+        Action::Pan,
+        Action::PanUp,
+        Action::PanDown,
+        Action::PanLeft,
+        Action::PanRight,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::FitBoard,
+        Action::Restart,
+        Action::IncreaseSize,
+        Action::DecreaseSize,
+        Action::ToggleWireframe,
+        Action::Save,
+        Action::Pause,
+        Action::Menu,
+        Action::Quit,
+    ];
+
+    /// The action's key in the `[controls]` section of the config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Reveal => "reveal",
+            Action::Flag => "flag",
+            Action::Chord => "chord",
+            Action::CursorUp => "cursor_up",
+            Action::CursorDown => "cursor_down",
+            Action::CursorLeft => "cursor_left",
+            Action::CursorRight => "cursor_right",
+            Action::Pan => "pan",
+            Action::PanUp => "pan_up",
+            Action::PanDown => "pan_down",
+            Action::PanLeft => "pan_left",
+            Action::PanRight => "pan_right",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::FitBoard => "fit_board",
+            Action::Restart => "restart",
+            Action::IncreaseSize => "increase_size",
+            Action::DecreaseSize => "decrease_size",
+            Action::ToggleWireframe => "wireframe",
+            Action::Save => "save",
+            Action::Pause => "pause",
+            Action::Menu => "menu",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// A key, typed character, mouse button or gamepad button.
+///
+/// Named keys are matched by their position on a US keyboard, whatever the
+/// layout, while characters follow the layout: `"+"` is whichever key types
+/// a plus sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Key(Key),
+    Char(char),
+    Mouse(MouseButton),
+    /// The d-pad buttons also stand for the left stick.
+    Gamepad(GamepadButton),
+}
+
+const KEY_NAMES: [(&str, Key); 47] = [
+    ("space", Key::Space),
+    ("enter", Key::Enter),
+    ("escape", Key::Escape),
+    ("tab", Key::Tab),
+    ("backspace", Key::Backspace),
+    ("insert", Key::Insert),
+    ("delete", Key::Delete),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("page-up", Key::PageUp),
+    ("page-down", Key::PageDown),
+    ("pause", Key::Pause),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("f1", Key::F1),
+    ("f2", Key::F2),
+    ("f3", Key::F3),
+    ("f4", Key::F4),
+    ("f5", Key::F5),
+    ("f6", Key::F6),
+    ("f7", Key::F7),
+    ("f8", Key::F8),
+    ("f9", Key::F9),
+    ("f10", Key::F10),
+    ("f11", Key::F11),
+    ("f12", Key::F12),
+    ("kp-0", Key::Kp0),
+    ("kp-1", Key::Kp1),
+    ("kp-2", Key::Kp2),
+    ("kp-3", Key::Kp3),
+    ("kp-4", Key::Kp4),
+    ("kp-5", Key::Kp5),
+    ("kp-6", Key::Kp6),
+    ("kp-7", Key::Kp7),
+    ("kp-8", Key::Kp8),
+    ("kp-9", Key::Kp9),
+    ("kp-add", Key::KpAdd),
+    ("kp-subtract", Key::KpSubtract),
+    ("kp-enter", Key::KpEnter),
+    ("left-shift", Key::LeftShift),
+    ("right-shift", Key::RightShift),
+    ("left-control", Key::LeftControl),
+    ("right-control", Key::RightControl),
+    ("left-alt", Key::LeftAlt),
+    ("right-alt", Key::RightAlt),
+];
+
+const MOUSE_NAMES: [(&str, MouseButton); 8] = [
+    ("mouse-left", MouseButton::Button1),
+    ("mouse-right", MouseButton::Button2),
+    ("mouse-middle", MouseButton::Button3),
+    ("mouse-4", MouseButton::Button4),
+    ("mouse-5", MouseButton::Button5),
+    ("mouse-6", MouseButton::Button6),
+    ("mouse-7", MouseButton::Button7),
+    ("mouse-8", MouseButton::Button8),
+];
+
+const GAMEPAD_NAMES: [(&str, GamepadButton); 15] = [
+    ("gamepad-a", GamepadButton::ButtonA),
+    ("gamepad-b", GamepadButton::ButtonB),
+    ("gamepad-x", GamepadButton::ButtonX),
+    ("gamepad-y", GamepadButton::ButtonY),
+    ("gamepad-left-bumper", GamepadButton::ButtonLeftBumper),
+    ("gamepad-right-bumper", GamepadButton::ButtonRightBumper),
+    ("gamepad-back", GamepadButton::ButtonBack),
+    ("gamepad-start", GamepadButton::ButtonStart),
+    ("gamepad-guide", GamepadButton::ButtonGuide),
+    ("gamepad-left-thumb", GamepadButton::ButtonLeftThumb),
+    ("gamepad-right-thumb", GamepadButton::ButtonRightThumb),
+    ("gamepad-up", GamepadButton::ButtonDpadUp),
+    ("gamepad-down", GamepadButton::ButtonDpadDown),
+    ("gamepad-left", GamepadButton::ButtonDpadLeft),
+    ("gamepad-right", GamepadButton::ButtonDpadRight),
+];
+
+impl Input {
+    /// Parses a binding from the config: a key name such as `"space"` or
+    /// `"kp-add"`, a mouse button such as `"mouse-middle"`, a gamepad button
+    /// such as `"gamepad-a"`, or a single character.
+    pub fn from_name(name: &str) -> Result<Input, String> {
+        let mut chars = name.chars();
+
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Input::Char(c.to_ascii_lowercase()));
+        }
+
+        let name = name.to_ascii_lowercase();
+
+        KEY_NAMES
+            .iter()
+            .find(|&&(key_name, _)| key_name == name)
+            .map(|&(_, key)| Input::Key(key))
+            .or_else(|| {
+                MOUSE_NAMES
+                    .iter()
+                    .find(|&&(button_name, _)| button_name == name)
+                    .map(|&(_, button)| Input::Mouse(button))
+            })
+            .or_else(|| {
+                GAMEPAD_NAMES
+                    .iter()
+                    .find(|&&(button_name, _)| button_name == name)
+                    .map(|&(_, button)| Input::Gamepad(button))
+            })
+            .ok_or_else(|| format!("unknown input \"{}\"", name))
+    }
+
+    /// The name `from_name` parses into this input.
+    pub fn name(&self) -> String {
+        let name = match self {
+            Input::Char(c) => return c.to_string(),
+            Input::Key(key) => KEY_NAMES
+                .iter()
+                .find(|&&(_, named)| named == *key)
+                .map(|&(name, _)| name),
+            Input::Mouse(button) => MOUSE_NAMES
+                .iter()
+                .find(|&&(_, named)| named == *button)
+                .map(|&(name, _)| name),
+            Input::Gamepad(button) => GAMEPAD_NAMES
+                .iter()
+                .find(|&&(_, named)| named == *button)
+                .map(|&(name, _)| name),
+        };
+
+        // Keys can only be bound by name, so they always have one.
+        name.unwrap_or_default().to_owned()
+    }
+
+    /// Letters match whatever the case they were typed in.
+    pub fn from_char(c: char) -> Input {
+        Input::Char(c.to_ascii_lowercase())
+    }
+}
+
+/// Which inputs trigger which actions. An action can have any number of
+/// bindings, and an input can trigger several actions.
+#[derive(Debug, Clone)]
+pub struct Bindings {
+    bindings: Vec<(Input, Action)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        let bindings = vec![
+            (Input::Mouse(MouseButton::Button1), Action::Reveal),
+            (Input::Key(Key::Enter), Action::Reveal),
+            (Input::Mouse(MouseButton::Button2), Action::Flag),
+            (Input::Char('f'), Action::Flag),
+            (Input::Key(Key::Space), Action::Chord),
+            (Input::Key(Key::Up), Action::CursorUp),
+            (Input::Char('k'), Action::CursorUp),
+            (Input::Key(Key::Down), Action::CursorDown),
+            (Input::Char('j'), Action::CursorDown),
+            (Input::Key(Key::Left), Action::CursorLeft),
+            (Input::Char('h'), Action::CursorLeft),
+            (Input::Key(Key::Right), Action::CursorRight),
+            (Input::Char('l'), Action::CursorRight),
+            (Input::Gamepad(GamepadButton::ButtonA), Action::Reveal),
+            (Input::Gamepad(GamepadButton::ButtonX), Action::Flag),
+            (Input::Gamepad(GamepadButton::ButtonB), Action::Chord),
+            (
+                Input::Gamepad(GamepadButton::ButtonDpadUp),
+                Action::CursorUp,
+            ),
+            (
+                Input::Gamepad(GamepadButton::ButtonDpadDown),
+                Action::CursorDown,
+            ),
+            (
+                Input::Gamepad(GamepadButton::ButtonDpadLeft),
+                Action::CursorLeft,
+            ),
+            (
+                Input::Gamepad(GamepadButton::ButtonDpadRight),
+                Action::CursorRight,
+            ),
+            (Input::Mouse(MouseButton::Button3), Action::Pan),
+            (Input::Key(Key::Kp8), Action::PanUp),
+            (Input::Key(Key::Kp2), Action::PanDown),
+            (Input::Key(Key::Kp4), Action::PanLeft),
+            (Input::Key(Key::Kp6), Action::PanRight),
+            (Input::Key(Key::PageUp), Action::ZoomIn),
+            (
+                Input::Gamepad(GamepadButton::ButtonRightBumper),
+                Action::ZoomIn,
+            ),
+            (Input::Key(Key::PageDown), Action::ZoomOut),
+            (
+                Input::Gamepad(GamepadButton::ButtonLeftBumper),
+                Action::ZoomOut,
+            ),
+            (Input::Key(Key::Home), Action::FitBoard),
+            (Input::Gamepad(GamepadButton::ButtonY), Action::FitBoard),
+            (Input::Gamepad(GamepadButton::ButtonBack), Action::Restart),
+            (Input::Char('r'), Action::Restart),
+            (Input::Char('+'), Action::IncreaseSize),
+            (Input::Char('='), Action::IncreaseSize),
+            (Input::Char('-'), Action::DecreaseSize),
+            (Input::Char('w'), Action::ToggleWireframe),
+            (Input::Char('s'), Action::Save),
+            (Input::Char('p'), Action::Pause),
+            (Input::Key(Key::Pause), Action::Pause),
+            (Input::Key(Key::Escape), Action::Menu),
+            (Input::Gamepad(GamepadButton::ButtonStart), Action::Menu),
+        ];
+
+        Bindings { bindings }
+    }
+}
+
+impl Bindings {
+    /// Replaces every binding of `action` with `inputs`.
+    pub fn bind(&mut self, action: Action, inputs: &[Input]) {
+        self.bindings.retain(|&(_, bound)| bound != action);
+        self.bindings
+            .extend(inputs.iter().map(|&input| (input, action)));
+    }
+
+    pub fn inputs(&self, action: Action) -> Vec<Input> {
+        self.bindings
+            .iter()
+            .filter(|&&(_, bound)| bound == action)
+            .map(|&(input, _)| input)
+            .collect()
+    }
+
+    pub fn actions(&self, input: Input) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|&&(bound, _)| bound == input)
+            .map(|&(_, action)| action)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_kind_of_input() {
+        assert_eq!(Input::from_name("space"), Ok(Input::Key(Key::Space)));
+        assert_eq!(Input::from_name("KP-Add"), Ok(Input::Key(Key::KpAdd)));
+        assert_eq!(
+            Input::from_name("mouse-middle"),
+            Ok(Input::Mouse(MouseButton::Button3))
+        );
+        assert_eq!(
+            Input::from_name("gamepad-a"),
+            Ok(Input::Gamepad(GamepadButton::ButtonA))
+        );
+        assert_eq!(Input::from_name("R"), Ok(Input::Char('r')));
+        assert_eq!(Input::from_name("+"), Ok(Input::Char('+')));
+        assert!(Input::from_name("hyper").is_err());
+    }
+
+    #[test]
+    fn names_parse_back_into_their_input() {
+        let inputs = Bindings::default().bindings.into_iter().map(|(i, _)| i);
+
+        for input in inputs {
+            assert_eq!(Input::from_name(&input.name()), Ok(input));
+        }
+    }
+
+    #[test]
+    fn action_names_are_unique() {
+        for action in Action::ALL {
+            assert_eq!(Action::from_name(action.name()), Some(action));
+        }
+        assert_eq!(Action::from_name("teleport"), None);
+    }
+
+    #[test]
+    fn binding_an_action_replaces_its_defaults() {
+        let mut bindings = Bindings::default();
+        bindings.bind(Action::Restart, &[Input::Key(Key::F2)]);
+
+        assert_eq!(bindings.inputs(Action::Restart), [Input::Key(Key::F2)]);
+        assert!(bindings.actions(Input::Char('r')).is_empty());
+        assert_eq!(bindings.actions(Input::Char('=')), [Action::IncreaseSize]);
+    }
+}
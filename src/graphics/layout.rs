@@ -0,0 +1,92 @@
+/// Height of the HUD bar, relative to the longer side of the board.
+pub const HUD_HEIGHT: f64 = 0.12;
+
+/// An area of the window in pixels, from the bottom left like GL viewports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub fn use_as_viewport(&self) {
+        unsafe {
+            gl::Viewport(
+                self.x.round() as i32,
+                self.y.round() as i32,
+                self.width.round() as i32,
+                self.height.round() as i32,
+            );
+        }
+    }
+
+    /// Converts a window position, in pixels from the top left like cursor
+    /// positions, to normalized device coordinates in this area.
+    pub fn to_ndc(
+        &self,
+        x_px: f64,
+        y_px: f64,
+        window_height: f64,
+    ) -> (f64, f64) {
+        let y = window_height - y_px;
+
+        (
+            (x_px - self.x) / self.width * 2.0 - 1.0,
+            (y - self.y) / self.height * 2.0 - 1.0,
+        )
+    }
+}
+
+/// Where the HUD bar and the board go: the largest board that fits under
+/// the bar with square tiles, centred in the window. The bar is as wide as
+/// the longer side of the board.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    /// The whole window, for overlays like the menu.
+    pub window: Rect,
+    pub hud: Rect,
+    pub board: Rect,
+}
+
+impl Layout {
+    /// The layout of a board `width` by `height` tiles.
+    pub fn new(
+        window_width: f64,
+        window_height: f64,
+        width: isize,
+        height: isize,
+    ) -> Self {
+        // The sides of the board, relative to its longer one.
+        let longest = width.max(height) as f64;
+        let (board_width, board_height) =
+            (width as f64 / longest, height as f64 / longest);
+
+        let size =
+            window_width.min(window_height / (board_height + HUD_HEIGHT));
+        let x = (window_width - size) / 2.0;
+        let y = (window_height - size * (board_height + HUD_HEIGHT)) / 2.0;
+
+        Layout {
+            window: Rect {
+                x: 0.0,
+                y: 0.0,
+                width: window_width,
+                height: window_height,
+            },
+            board: Rect {
+                x: x + size * (1.0 - board_width) / 2.0,
+                y,
+                width: size * board_width,
+                height: size * board_height,
+            },
+            hud: Rect {
+                x,
+                y: y + size * board_height,
+                width: size,
+                height: size * HUD_HEIGHT,
+            },
+        }
+    }
+}
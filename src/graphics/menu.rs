@@ -0,0 +1,542 @@
+use crate::{
+    cli::Preset,
+    config::Config,
+    game::{Chording, FirstClick, Game, Rules},
+    graphics::{
+        input::Action,
+        layout::Rect,
+        overlay::Overlay,
+        shader::Shader,
+        text::{self, Align, TextRenderer, TextStyle},
+        texture::Filter,
+        theme::Theme,
+    },
+    statistics::Statistics,
+    MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH,
+};
+
+const PRESETS: [Preset; 3] =
+    [Preset::Beginner, Preset::Intermediate, Preset::Expert];
+const FIRST_CLICKS: [FirstClick; 3] = [
+    FirstClick::SafeArea,
+    FirstClick::SafeTile,
+    FirstClick::Unprotected,
+];
+const CHORDINGS: [Chording; 3] =
+    [Chording::Click, Chording::KeyOnly, Chording::Disabled];
+const FILTERS: [Filter; 3] =
+    [Filter::Trilinear, Filter::Linear, Filter::Nearest];
+
+const TEXT_COLOUR: [f32; 4] = [0.9, 0.9, 0.9, 1.0];
+const SELECTED_COLOUR: [f32; 4] = [1.0, 0.8, 0.0, 1.0];
+const DIM_COLOUR: [f32; 4] = [0.0, 0.0, 0.0, 0.5];
+const PANEL_COLOUR: [f32; 4] = [0.12, 0.12, 0.14, 0.95];
+const HIGHLIGHT_COLOUR: [f32; 4] = [1.0, 1.0, 1.0, 0.1];
+
+/// Width of the panel, in characters.
+const PANEL_COLUMNS: f32 = 30.0;
+/// Height of a row, relative to the size of the text.
+const ROW_HEIGHT: f32 = 1.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Resume,
+    NewGame,
+    Difficulty,
+    Width,
+    Height,
+    Mines,
+    FirstClick,
+    Chording,
+    Theme,
+    Filter,
+    Statistics,
+    Quit,
+    Back,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Main,
+    Statistics,
+}
+
+impl Page {
+    fn title(&self) -> &'static str {
+        match self {
+            Page::Main => "Paused",
+            Page::Statistics => "Statistics",
+        }
+    }
+
+    fn items(&self) -> &'static [Item] {
+        match self {
+            Page::Main => &[
+                Item::Resume,
+                Item::NewGame,
+                Item::Difficulty,
+                Item::Width,
+                Item::Height,
+                Item::Mines,
+                Item::FirstClick,
+                Item::Chording,
+                Item::Theme,
+                Item::Filter,
+                Item::Statistics,
+                Item::Quit,
+            ],
+            Page::Statistics => &[Item::Back],
+        }
+    }
+}
+
+/// What the window has to do after the menu handled an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// A new game started, on a board that may have another size.
+    NewGame,
+    Quit,
+}
+
+/// The overlay opened with Escape. The game is paused while it is open,
+/// and it takes all the input, mouse and keyboard alike.
+///
+/// The board size, mine count and rules apply to the next new game, so a
+/// game is played by one set of rules from start to end. Themes apply
+/// right away.
+pub struct Menu {
+    open: bool,
+    page: Page,
+    selected: usize,
+    width: isize,
+    height: isize,
+    mines: isize,
+    rules: Rules,
+    themes: Vec<Theme>,
+    /// Index of the picked theme in `themes`. Loading it is up to the
+    /// caller, which follows `theme`.
+    theme: usize,
+    filter: Filter,
+    overlay: Overlay,
+}
+
+impl Menu {
+    pub fn new(config: &Config) -> Self {
+        let mut themes = Theme::available();
+        let theme = config
+            .theme
+            .as_ref()
+            .map_or(0, |name| Theme::find(&mut themes, name));
+
+        Menu {
+            open: false,
+            page: Page::Main,
+            selected: 0,
+            width: config.width,
+            height: config.height,
+            mines: 0,
+            rules: config.rules,
+            themes,
+            theme,
+            filter: config.texture_filter,
+            overlay: Overlay::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Opens the menu on its first page, with the settings of `game`.
+    pub fn open(&mut self, game: &Game) {
+        self.open = true;
+        self.page = Page::Main;
+        self.selected = 0;
+        self.width = game.width;
+        self.height = game.height;
+        self.mines = game.mine_count;
+        self.rules = game.rules;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// The picked theme and its index, which changes when another one is
+    /// picked.
+    pub fn theme(&self) -> (usize, &Theme) {
+        (self.theme, &self.themes[self.theme])
+    }
+
+    /// How the tiles are to be sampled, which changes when picked.
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    pub fn handle(
+        &mut self,
+        action: Action,
+        game: &mut Game,
+    ) -> Option<Command> {
+        let items = self.page.items();
+
+        match action {
+            Action::CursorUp => {
+                self.selected = (self.selected + items.len() - 1) % items.len();
+            }
+            Action::CursorDown => {
+                self.selected = (self.selected + 1) % items.len();
+            }
+            Action::CursorLeft | Action::Flag => self.change(-1),
+            Action::CursorRight => self.change(1),
+            Action::Reveal => return self.activate(game),
+            Action::Menu => match self.page {
+                Page::Main => self.close(),
+                Page::Statistics => self.show(Page::Main),
+            },
+            _ => (),
+        }
+
+        None
+    }
+
+    /// Changes the selected value by `steps`, like the mouse wheel does.
+    pub fn scroll(&mut self, steps: isize) {
+        self.change(steps);
+    }
+
+    fn activate(&mut self, game: &mut Game) -> Option<Command> {
+        match self.page.items()[self.selected] {
+            Item::Resume => self.close(),
+            Item::NewGame => {
+                game.rules = self.rules;
+                game.resize(self.width, self.height);
+                game.set_mine_count(self.mines);
+                self.close();
+                return Some(Command::NewGame);
+            }
+            Item::Statistics => self.show(Page::Statistics),
+            Item::Back => self.show(Page::Main),
+            Item::Quit => return Some(Command::Quit),
+            _ => self.change(1),
+        }
+
+        None
+    }
+
+    fn show(&mut self, page: Page) {
+        let item = match page {
+            Page::Main => Item::Statistics,
+            Page::Statistics => Item::Back,
+        };
+
+        self.page = page;
+        self.selected =
+            page.items().iter().position(|&i| i == item).unwrap_or(0);
+    }
+
+    fn change(&mut self, steps: isize) {
+        match self.page.items()[self.selected] {
+            Item::Difficulty => {
+                let preset = match self.preset() {
+                    Some(preset) => {
+                        let index = PRESETS.iter().position(|&p| p == preset);
+                        cycle(index.unwrap_or(0), steps, PRESETS.len())
+                    }
+                    None if steps > 0 => 0,
+                    None => PRESETS.len() - 1,
+                };
+                (self.width, self.height, self.mines) = PRESETS[preset].board();
+            }
+            Item::Width => {
+                self.width = (self.width + steps).clamp(MIN_WIDTH, MAX_WIDTH);
+            }
+            Item::Height => {
+                self.height =
+                    (self.height + steps).clamp(MIN_HEIGHT, MAX_HEIGHT);
+            }
+            Item::Mines => self.mines += steps,
+            Item::FirstClick => {
+                let index = FIRST_CLICKS
+                    .iter()
+                    .position(|&f| f == self.rules.first_click);
+                self.rules.first_click = FIRST_CLICKS
+                    [cycle(index.unwrap_or(0), steps, FIRST_CLICKS.len())];
+            }
+            Item::Chording => {
+                let index =
+                    CHORDINGS.iter().position(|&c| c == self.rules.chording);
+                self.rules.chording = CHORDINGS
+                    [cycle(index.unwrap_or(0), steps, CHORDINGS.len())];
+            }
+            Item::Theme => {
+                self.theme = cycle(self.theme, steps, self.themes.len());
+            }
+            Item::Filter => {
+                let index = FILTERS.iter().position(|&f| f == self.filter);
+                self.filter =
+                    FILTERS[cycle(index.unwrap_or(0), steps, FILTERS.len())];
+            }
+            _ => return,
+        }
+
+        let max_mines =
+            self.rules.first_click.max_mines(self.width, self.height);
+        self.mines = self.mines.clamp(0, max_mines);
+    }
+
+    fn preset(&self) -> Option<Preset> {
+        PRESETS.into_iter().find(|preset| {
+            preset.board() == (self.width, self.height, self.mines)
+        })
+    }
+
+    /// Selects the item under a window position, in pixels from the top
+    /// left, returning whether there is one.
+    pub fn select_at(&mut self, x_px: f64, y_px: f64, area: &Rect) -> bool {
+        let y = area.height - y_px;
+        let rows = self.rows(area);
+        let first_item = rows.len() - self.page.items().len();
+
+        let item = rows[first_item..].iter().position(|row| {
+            (row.x..=row.x + row.width).contains(&x_px)
+                && (row.y..=row.y + row.height).contains(&y)
+        });
+
+        if let Some(item) = item {
+            self.selected = item;
+        }
+
+        item.is_some()
+    }
+
+    /// Lines of text shown above the items of the page.
+    fn info(&self, game: &Game, statistics: &Statistics) -> Vec<String> {
+        match self.page {
+            Page::Main => Vec::new(),
+            Page::Statistics => {
+                let record =
+                    statistics.record(game.width, game.height, game.mine_count);
+                let total = statistics.total();
+                let best_time = match record.best_time {
+                    Some(time) => format!("{:.3} s", time.as_secs_f64()),
+                    None => "-".to_owned(),
+                };
+
+                vec![
+                    format!(
+                        "{}x{}, {} mines",
+                        game.width, game.height, game.mine_count
+                    ),
+                    format!("Played: {}", record.played),
+                    format!(
+                        "Won: {} ({:.0}%)",
+                        record.won,
+                        record.win_rate() * 100.0
+                    ),
+                    format!("Best time: {}", best_time),
+                    format!(
+                        "All boards: {} of {} won",
+                        total.won, total.played
+                    ),
+                ]
+            }
+        }
+    }
+
+    fn label(&self, item: Item) -> (&'static str, String) {
+        match item {
+            Item::Resume => ("Resume", String::new()),
+            Item::NewGame => ("New game", String::new()),
+            Item::Difficulty => (
+                "Board",
+                match self.preset() {
+                    Some(Preset::Beginner) => "Beginner",
+                    Some(Preset::Intermediate) => "Intermediate",
+                    Some(Preset::Expert) => "Expert",
+                    None => "Custom",
+                }
+                .to_owned(),
+            ),
+            Item::Width => ("Width", self.width.to_string()),
+            Item::Height => ("Height", self.height.to_string()),
+            Item::Mines => ("Mines", self.mines.to_string()),
+            Item::FirstClick => (
+                "First click",
+                match self.rules.first_click {
+                    FirstClick::SafeArea => "Safe area",
+                    FirstClick::SafeTile => "Safe tile",
+                    FirstClick::Unprotected => "Unprotected",
+                }
+                .to_owned(),
+            ),
+            Item::Chording => (
+                "Chording",
+                match self.rules.chording {
+                    Chording::Click => "Click",
+                    Chording::KeyOnly => "Key only",
+                    Chording::Disabled => "Disabled",
+                }
+                .to_owned(),
+            ),
+            Item::Theme => ("Theme", self.themes[self.theme].name.clone()),
+            Item::Filter => (
+                "Filtering",
+                match self.filter {
+                    Filter::Nearest => "Nearest",
+                    Filter::Linear => "Linear",
+                    Filter::Trilinear => "Trilinear",
+                }
+                .to_owned(),
+            ),
+            Item::Statistics => ("Statistics", String::new()),
+            Item::Quit => ("Quit", String::new()),
+            Item::Back => ("Back", String::new()),
+        }
+    }
+
+    fn text_size(area: &Rect) -> f32 {
+        (area.width.min(area.height) / 24.0) as f32
+    }
+
+    /// The rows of the panel from the top, in pixels of `area`: the title,
+    /// the info lines, then the items.
+    fn rows(&self, area: &Rect) -> Vec<Rect> {
+        let size = Self::text_size(area) as f64;
+        let row_height = size * ROW_HEIGHT as f64;
+        let width =
+            text::measure("M", size as f32).0 as f64 * PANEL_COLUMNS as f64;
+        let count = 1 + self.info_lines() + self.page.items().len();
+        let top = (area.height + row_height * count as f64) / 2.0;
+
+        (0..count)
+            .map(|row| Rect {
+                x: (area.width - width) / 2.0,
+                y: top - row_height * (row + 1) as f64,
+                width,
+                height: row_height,
+            })
+            .collect()
+    }
+
+    fn info_lines(&self) -> usize {
+        match self.page {
+            Page::Main => 0,
+            Page::Statistics => 5,
+        }
+    }
+
+    /// Draws the menu over the whole window, `area` being its viewport.
+    pub fn draw(
+        &self,
+        overlay_shader: &Shader,
+        text_shader: &Shader,
+        text: &mut TextRenderer,
+        game: &Game,
+        statistics: &Statistics,
+        area: &Rect,
+    ) {
+        let size = Self::text_size(area);
+        let rows = self.rows(area);
+        let padding = size as f64;
+        let panel = Rect {
+            x: rows[0].x - padding,
+            y: rows[rows.len() - 1].y - padding / 2.0,
+            width: rows[0].width + padding * 2.0,
+            height: rows.len() as f64 * rows[0].height + padding,
+        };
+
+        self.overlay.draw(overlay_shader, area, area, DIM_COLOUR);
+        self.overlay
+            .draw(overlay_shader, &panel, area, PANEL_COLOUR);
+
+        // Text is placed by the top of its line, centred in the row.
+        let text_top = |row: &Rect| {
+            (row.y + row.height) as f32 - (row.height as f32 - size) / 2.0
+        };
+        let style = TextStyle {
+            size,
+            colour: TEXT_COLOUR,
+            align: Align::Center,
+        };
+
+        let title = &rows[0];
+        text.queue(
+            self.page.title(),
+            ((title.x + title.width / 2.0) as f32, text_top(title)),
+            &TextStyle {
+                size: size * 1.25,
+                ..style
+            },
+        );
+
+        let info = self.info(game, statistics);
+        for (line, row) in info.iter().zip(&rows[1..]) {
+            text.queue(
+                line,
+                (row.x as f32, text_top(row)),
+                &TextStyle {
+                    align: Align::Left,
+                    ..style
+                },
+            );
+        }
+
+        let items = self.page.items();
+        let item_rows = &rows[rows.len() - items.len()..];
+        for (index, (&item, row)) in items.iter().zip(item_rows).enumerate() {
+            let colour = if index == self.selected {
+                let highlight = Rect {
+                    x: panel.x,
+                    width: panel.width,
+                    ..*row
+                };
+                self.overlay.draw(
+                    overlay_shader,
+                    &highlight,
+                    area,
+                    HIGHLIGHT_COLOUR,
+                );
+                SELECTED_COLOUR
+            } else {
+                TEXT_COLOUR
+            };
+            let (label, value) = self.label(item);
+            let top = text_top(row);
+
+            if value.is_empty() {
+                text.queue(
+                    label,
+                    ((row.x + row.width / 2.0) as f32, top),
+                    &TextStyle { colour, ..style },
+                );
+                continue;
+            }
+
+            text.queue(
+                label,
+                (row.x as f32, top),
+                &TextStyle {
+                    colour,
+                    align: Align::Left,
+                    ..style
+                },
+            );
+            text.queue(
+                &format!("< {} >", value),
+                ((row.x + row.width) as f32, top),
+                &TextStyle {
+                    colour,
+                    align: Align::Right,
+                    ..style
+                },
+            );
+        }
+
+        text.draw(text_shader, area);
+    }
+}
+
+fn cycle(index: usize, steps: isize, len: usize) -> usize {
+    (index as isize + steps).rem_euclid(len as isize) as usize
+}
@@ -0,0 +1,50 @@
+use crate::graphics::{camera::Camera, quad::Quad, shader::Shader};
+
+/// Where the minimap sits in the board viewport, in normalized device
+/// coordinates: left, bottom, right and top.
+pub const RECT: [f32; 4] = [0.55, -0.95, 0.95, -0.55];
+
+/// A corner overview of the whole board, shown while zoomed in. It reads
+/// the tile states from the SSBO the board is drawn from.
+pub struct Minimap {
+    quad: Quad,
+}
+
+#[allow(clippy::new_without_default)]
+impl Minimap {
+    pub fn new() -> Self {
+        Minimap { quad: Quad::new() }
+    }
+
+    pub fn draw(
+        &self,
+        shader: &Shader,
+        camera: &Camera,
+        width: isize,
+        height: isize,
+    ) {
+        shader.use_program();
+        shader.set_vec4("rect", RECT);
+        shader.set_vec4("viewArea", camera.visible_area().map(|x| x as f32));
+        shader.set_int("boardWidth", width as i32);
+        shader.set_int("boardHeight", height as i32);
+
+        self.quad.draw();
+    }
+}
+
+/// The board position shown under `point` of the board viewport, if it is
+/// on the minimap.
+pub fn board_position(point: (f64, f64)) -> Option<(f64, f64)> {
+    let [left, bottom, right, top] = RECT.map(|x| x as f64);
+
+    if !(left..=right).contains(&point.0) || !(bottom..=top).contains(&point.1)
+    {
+        return None;
+    }
+
+    Some((
+        (point.0 - left) / (right - left) * 2.0 - 1.0,
+        (point.1 - bottom) / (top - bottom) * 2.0 - 1.0,
+    ))
+}
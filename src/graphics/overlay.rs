@@ -0,0 +1,41 @@
+use crate::graphics::{layout::Rect, quad::Quad, shader::Shader};
+
+/// Draws flat, possibly translucent rectangles over everything else, to
+/// dim the board or back menus and messages.
+pub struct Overlay {
+    quad: Quad,
+}
+
+#[allow(clippy::new_without_default)]
+impl Overlay {
+    pub fn new() -> Self {
+        Overlay { quad: Quad::new() }
+    }
+
+    /// Fills `rect`, in pixels of the current `viewport`.
+    pub fn draw(
+        &self,
+        shader: &Shader,
+        rect: &Rect,
+        viewport: &Rect,
+        colour: [f32; 4],
+    ) {
+        let to_ndc = |x: f64, size: f64| (x / size * 2.0 - 1.0) as f32;
+
+        shader.use_program();
+        shader.set_vec4(
+            "rect",
+            [
+                to_ndc(rect.x, viewport.width),
+                to_ndc(rect.y, viewport.height),
+                to_ndc(rect.x + rect.width, viewport.width),
+                to_ndc(rect.y + rect.height, viewport.height),
+            ],
+        );
+        shader.set_vec4("color", colour);
+
+        unsafe { gl::Disable(gl::DEPTH_TEST) };
+        self.quad.draw();
+        unsafe { gl::Enable(gl::DEPTH_TEST) };
+    }
+}
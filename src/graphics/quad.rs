@@ -0,0 +1,26 @@
+use crate::graphics::{debug, gl_wrapper::VAO};
+
+/// A quad drawn as a 4 vertex triangle strip, for the shaders that place
+/// their corners from `gl_VertexID` instead of reading vertices: the
+/// board, the minimap, the HUD and the overlay. Core profile contexts
+/// still need a vertex array bound to draw, so the quad keeps an empty
+/// one.
+pub struct Quad {
+    vao: VAO,
+}
+
+#[allow(clippy::new_without_default)]
+impl Quad {
+    pub fn new() -> Self {
+        Quad { vao: VAO::new() }
+    }
+
+    /// Draws the quad with the program in use.
+    pub fn draw(&self) {
+        self.vao.bind();
+        unsafe {
+            gl::DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+        }
+        debug::check("Quad::draw");
+    }
+}
@@ -1,17 +1,132 @@
-use std::ffi::CString;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ffi::CString,
+    fmt, ptr,
+};
 
-use log::info;
+use gl::types::{GLchar, GLenum, GLint, GLsizei, GLuint};
+use log::{debug, error, info};
 
+use crate::graphics::debug as gl_debug;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Vertex,
+    Fragment,
+}
+
+impl Stage {
+    fn gl_type(self) -> GLenum {
+        match self {
+            Stage::Vertex => gl::VERTEX_SHADER,
+            Stage::Fragment => gl::FRAGMENT_SHADER,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Vertex => write!(f, "vertex"),
+            Stage::Fragment => write!(f, "fragment"),
+        }
+    }
+}
+
+/// Why a program couldn't be built. The logs are the driver's, as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShaderError {
+    /// The source holds a NUL byte, which GL can't take.
+    NulInSource(Stage),
+    Compile {
+        stage: Stage,
+        log: String,
+    },
+    Link {
+        log: String,
+    },
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShaderError::NulInSource(stage) => {
+                write!(f, "the {} shader source holds a NUL byte", stage)
+            }
+            ShaderError::Compile { stage, log } => {
+                write!(f, "failed to compile the {} shader:\n{}", stage, log)
+            }
+            ShaderError::Link { log } => {
+                write!(f, "failed to link the program:\n{}", log)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShaderError {}
+
+/// An active uniform outside of any block, as found at link time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uniform {
+    /// The name in the source, without the `[0]` GL adds to arrays.
+    pub name: String,
+    pub location: GLint,
+    /// The GL type, like `gl::FLOAT_VEC4` or `gl::SAMPLER_2D_ARRAY`.
+    pub kind: GLenum,
+    /// Number of elements, 1 unless it's an array.
+    pub size: GLint,
+}
+
+/// An active shader storage block, as found at link time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageBlock {
+    pub name: String,
+    pub binding: GLuint,
+    /// Bytes before the unsized array at the end of the block, if any.
+    pub data_size: GLint,
+}
+
+const SAMPLERS: [GLenum; 8] = [
+    gl::SAMPLER_1D,
+    gl::SAMPLER_2D,
+    gl::SAMPLER_3D,
+    gl::SAMPLER_CUBE,
+    gl::SAMPLER_1D_ARRAY,
+    gl::SAMPLER_2D_ARRAY,
+    gl::SAMPLER_2D_MULTISAMPLE,
+    gl::SAMPLER_BUFFER,
+];
+
+/// A linked program, with its interface looked up once when it's built.
+///
+/// Setting a uniform the program doesn't use does nothing, as it would in
+/// GL, since the driver drops those it optimises away. Setting one with a
+/// value of the wrong type is skipped and logged, since a hot-reloaded
+/// shader can change a uniform's type under the code setting it.
 pub struct Shader {
-    id: u32,
+    id: GLuint,
+    uniforms: HashMap<String, Uniform>,
+    storage_blocks: Vec<StorageBlock>,
+    /// Uniforms already logged as set with the wrong type, so they're
+    /// reported once rather than every frame.
+    mistyped: RefCell<HashSet<String>>,
 }
 
 impl Shader {
-    pub fn new(vertex_code: String, fragment_code: String) -> Self {
-        let vertex_shader =
-            Self::compile_shader(vertex_code.as_str(), gl::VERTEX_SHADER);
+    pub fn new(
+        vertex_code: &str,
+        fragment_code: &str,
+    ) -> Result<Self, ShaderError> {
+        let vertex_shader = Self::compile_shader(vertex_code, Stage::Vertex)?;
         let fragment_shader =
-            Self::compile_shader(fragment_code.as_str(), gl::FRAGMENT_SHADER);
+            match Self::compile_shader(fragment_code, Stage::Fragment) {
+                Ok(shader) => shader,
+                Err(error) => {
+                    unsafe { gl::DeleteShader(vertex_shader) };
+                    return Err(error);
+                }
+            };
 
         let id = Self::link_program(vertex_shader, fragment_shader);
 
@@ -20,14 +135,29 @@ impl Shader {
             gl::DeleteShader(fragment_shader);
         }
 
-        Self { id }
+        let id = id?;
+        let shader = Self {
+            id,
+            uniforms: reflect_uniforms(id),
+            storage_blocks: reflect_storage_blocks(id),
+            mistyped: RefCell::default(),
+        };
+        debug!(
+            "Program {} has uniforms {:?} and storage blocks {:?}",
+            id,
+            shader.uniforms.keys().collect::<Vec<_>>(),
+            shader.storage_blocks,
+        );
+
+        Ok(shader)
     }
 
-    fn compile_shader(source: &str, shader_type: u32) -> u32 {
-        let id = unsafe { gl::CreateShader(shader_type) };
-        let c_str = CString::new(source.as_bytes()).unwrap();
+    fn compile_shader(source: &str, stage: Stage) -> Result<u32, ShaderError> {
+        let c_str = CString::new(source.as_bytes())
+            .map_err(|_| ShaderError::NulInSource(stage))?;
+        let id = unsafe { gl::CreateShader(stage.gl_type()) };
         unsafe {
-            gl::ShaderSource(id, 1, &c_str.as_ptr(), std::ptr::null());
+            gl::ShaderSource(id, 1, &c_str.as_ptr(), ptr::null());
             gl::CompileShader(id);
         }
 
@@ -40,24 +170,23 @@ impl Shader {
             unsafe {
                 gl::GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut len);
             }
-            let error = create_whitespace_cstring_with_len(len as usize);
-            unsafe {
-                gl::GetShaderInfoLog(
-                    id,
-                    len,
-                    std::ptr::null_mut(),
-                    error.as_ptr() as *mut gl::types::GLchar,
-                );
-            }
-            panic!("{}", error.to_string_lossy().into_owned());
+            let log = read_string(len, |buffer| unsafe {
+                gl::GetShaderInfoLog(id, len, ptr::null_mut(), buffer);
+            });
+            unsafe { gl::DeleteShader(id) };
+
+            return Err(ShaderError::Compile { stage, log });
         }
 
         info!("Shader compiled successfully. (id: {})", id);
 
-        id
+        Ok(id)
     }
 
-    fn link_program(vertex_shader: u32, fragment_shader: u32) -> u32 {
+    fn link_program(
+        vertex_shader: u32,
+        fragment_shader: u32,
+    ) -> Result<u32, ShaderError> {
         let id = unsafe { gl::CreateProgram() };
         unsafe {
             gl::AttachShader(id, vertex_shader);
@@ -74,59 +203,218 @@ impl Shader {
             unsafe {
                 gl::GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut len);
             }
-            let error = create_whitespace_cstring_with_len(len as usize);
-            unsafe {
-                gl::GetProgramInfoLog(
-                    id,
-                    len,
-                    std::ptr::null_mut(),
-                    error.as_ptr() as *mut gl::types::GLchar,
-                );
-            }
-            panic!("{}", error.to_string_lossy().into_owned());
+            let log = read_string(len, |buffer| unsafe {
+                gl::GetProgramInfoLog(id, len, ptr::null_mut(), buffer);
+            });
+            unsafe { gl::DeleteProgram(id) };
+
+            return Err(ShaderError::Link { log });
         }
 
+        gl_debug::check("Shader::link_program");
         info!("Program linked successfully. (id: {})", id);
 
-        id
+        Ok(id)
     }
 
     pub fn use_program(&self) {
         unsafe {
             gl::UseProgram(self.id);
         }
+        gl_debug::check("Shader::use_program");
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<&Uniform> {
+        self.uniforms.get(name)
+    }
+
+    /// The active uniforms outside of blocks, in no particular order.
+    pub fn uniforms(&self) -> impl Iterator<Item = &Uniform> {
+        self.uniforms.values()
+    }
+
+    pub fn storage_blocks(&self) -> &[StorageBlock] {
+        &self.storage_blocks
+    }
+
+    /// Where to set `name`, if the program uses it with one of `kinds`.
+    fn location(&self, name: &str, kinds: &[GLenum]) -> Option<GLint> {
+        let uniform = self.uniforms.get(name)?;
+        if !kinds.contains(&uniform.kind) {
+            if self.mistyped.borrow_mut().insert(name.to_owned()) {
+                error!(
+                    "Uniform `{}` has GL type {:#x}, which can't be set to \
+                     {:#x?}; not setting it",
+                    name, uniform.kind, kinds
+                );
+            }
+            return None;
+        }
+
+        Some(uniform.location)
     }
 
     pub fn set_bool(&self, name: &str, value: bool) {
-        unsafe {
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.id, name.as_ptr() as *const i8),
-                value as i32,
-            );
+        if let Some(location) = self.location(name, &[gl::BOOL]) {
+            unsafe { gl::Uniform1i(location, value as i32) };
         }
     }
 
     pub fn set_int(&self, name: &str, value: i32) {
-        unsafe {
-            gl::Uniform1i(
-                gl::GetUniformLocation(self.id, name.as_ptr() as *const i8),
-                value,
-            );
+        if let Some(location) = self.location(name, &[gl::INT]) {
+            unsafe { gl::Uniform1i(location, value) };
         }
     }
 
     pub fn set_float(&self, name: &str, value: f32) {
+        if let Some(location) = self.location(name, &[gl::FLOAT]) {
+            unsafe { gl::Uniform1f(location, value) };
+        }
+    }
+
+    pub fn set_vec2(&self, name: &str, value: [f32; 2]) {
+        if let Some(location) = self.location(name, &[gl::FLOAT_VEC2]) {
+            unsafe { gl::Uniform2f(location, value[0], value[1]) };
+        }
+    }
+
+    pub fn set_vec3(&self, name: &str, value: [f32; 3]) {
+        if let Some(location) = self.location(name, &[gl::FLOAT_VEC3]) {
+            unsafe { gl::Uniform3f(location, value[0], value[1], value[2]) };
+        }
+    }
+
+    pub fn set_vec4(&self, name: &str, value: [f32; 4]) {
+        if let Some(location) = self.location(name, &[gl::FLOAT_VEC4]) {
+            unsafe {
+                gl::Uniform4f(location, value[0], value[1], value[2], value[3])
+            };
+        }
+    }
+
+    /// Sets a 3x3 matrix, given column by column.
+    pub fn set_mat3(&self, name: &str, value: &[f32; 9]) {
+        if let Some(location) = self.location(name, &[gl::FLOAT_MAT3]) {
+            unsafe {
+                gl::UniformMatrix3fv(location, 1, gl::FALSE, value.as_ptr())
+            };
+        }
+    }
+
+    /// Sets a 4x4 matrix, given column by column.
+    pub fn set_mat4(&self, name: &str, value: &[f32; 16]) {
+        if let Some(location) = self.location(name, &[gl::FLOAT_MAT4]) {
+            unsafe {
+                gl::UniformMatrix4fv(location, 1, gl::FALSE, value.as_ptr())
+            };
+        }
+    }
+
+    /// Makes a sampler read the texture bound to `unit`.
+    pub fn set_sampler(&self, name: &str, unit: u32) {
+        if let Some(location) = self.location(name, &SAMPLERS) {
+            unsafe { gl::Uniform1i(location, unit as i32) };
+        }
+    }
+}
+
+impl Drop for Shader {
+    fn drop(&mut self) {
         unsafe {
-            gl::Uniform1f(
-                gl::GetUniformLocation(self.id, name.as_ptr() as *const i8),
-                value,
-            );
+            gl::DeleteProgram(self.id);
         }
+        info!("Program deleted. (id: {})", self.id);
     }
 }
 
-fn create_whitespace_cstring_with_len(len: usize) -> CString {
-    let mut buffer: Vec<u8> = Vec::with_capacity(len + 1);
-    buffer.extend([b' '].iter().cycle().take(len));
-    unsafe { CString::from_vec_unchecked(buffer) }
+fn reflect_uniforms(program: GLuint) -> HashMap<String, Uniform> {
+    let properties = [gl::NAME_LENGTH, gl::TYPE, gl::ARRAY_SIZE, gl::LOCATION];
+
+    resources(program, gl::UNIFORM, &properties)
+        .filter_map(|(name, [_, kind, size, location])| {
+            // Uniforms in blocks have no location.
+            if location < 0 {
+                return None;
+            }
+            let name = name.strip_suffix("[0]").unwrap_or(&name).to_owned();
+
+            Some((
+                name.clone(),
+                Uniform {
+                    name,
+                    location,
+                    kind: kind as GLenum,
+                    size,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn reflect_storage_blocks(program: GLuint) -> Vec<StorageBlock> {
+    let properties =
+        [gl::NAME_LENGTH, gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE];
+
+    resources(program, gl::SHADER_STORAGE_BLOCK, &properties)
+        .map(|(name, [_, binding, data_size])| StorageBlock {
+            name,
+            binding: binding as GLuint,
+            data_size,
+        })
+        .collect()
+}
+
+/// The name and `properties` of each active resource of `interface`. The
+/// first property must be `gl::NAME_LENGTH`.
+fn resources<const N: usize>(
+    program: GLuint,
+    interface: GLenum,
+    properties: &[GLenum; N],
+) -> impl Iterator<Item = (String, [GLint; N])> + '_ {
+    let mut count = 0;
+    unsafe {
+        gl::GetProgramInterfaceiv(
+            program,
+            interface,
+            gl::ACTIVE_RESOURCES,
+            &mut count,
+        );
+    }
+
+    (0..count as GLuint).map(move |index| {
+        let mut values = [0; N];
+        unsafe {
+            gl::GetProgramResourceiv(
+                program,
+                interface,
+                index,
+                N as GLsizei,
+                properties.as_ptr(),
+                N as GLsizei,
+                ptr::null_mut(),
+                values.as_mut_ptr(),
+            );
+        }
+        let name = read_string(values[0], |buffer| unsafe {
+            gl::GetProgramResourceName(
+                program,
+                interface,
+                index,
+                values[0],
+                ptr::null_mut(),
+                buffer,
+            );
+        });
+
+        (name, values)
+    })
+}
+
+/// Reads a string GL writes with its NUL into a buffer of `len` bytes.
+fn read_string(len: GLint, read: impl FnOnce(*mut GLchar)) -> String {
+    let mut buffer = vec![0u8; len.max(1) as usize];
+    read(buffer.as_mut_ptr() as *mut GLchar);
+
+    let end = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..end]).into_owned()
 }
@@ -0,0 +1,158 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
+
+use log::{error, info};
+
+use crate::graphics::shader::{Shader, ShaderError};
+
+/// Names of the programs, each built from `<name>.vs` and `<name>.fs`.
+const NAMES: [&str; 5] = ["tile", "minimap", "hud", "text", "overlay"];
+
+/// The sources built into the binary, in the order of `NAMES`.
+const BUNDLED: [(&str, &str); 5] = [
+    (
+        include_str!("../../shaders/tile.vs"),
+        include_str!("../../shaders/tile.fs"),
+    ),
+    (
+        include_str!("../../shaders/minimap.vs"),
+        include_str!("../../shaders/minimap.fs"),
+    ),
+    (
+        include_str!("../../shaders/hud.vs"),
+        include_str!("../../shaders/hud.fs"),
+    ),
+    (
+        include_str!("../../shaders/text.vs"),
+        include_str!("../../shaders/text.fs"),
+    ),
+    (
+        include_str!("../../shaders/overlay.vs"),
+        include_str!("../../shaders/overlay.fs"),
+    ),
+];
+
+/// How often watched shaders are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Shaders read from a folder, rebuilt when their files change.
+struct Watch {
+    dir: PathBuf,
+    /// When the files of each program last changed, as of the last build.
+    modified: [Option<SystemTime>; 5],
+    polled: Instant,
+}
+
+/// Every program the game draws with.
+///
+/// They're built from the sources bundled in the binary, or for development
+/// from a folder that is watched for changes. A watched program that fails
+/// to build keeps the last one that worked, and its GLSL log is logged.
+pub struct Shaders {
+    pub tile: Shader,
+    pub minimap: Shader,
+    pub hud: Shader,
+    pub text: Shader,
+    pub overlay: Shader,
+    watch: Option<Watch>,
+}
+
+impl Shaders {
+    pub fn new() -> Result<Self, ShaderError> {
+        let [tile, minimap, hud, text, overlay] =
+            BUNDLED.map(|(vs, fs)| Shader::new(vs, fs));
+
+        Ok(Shaders {
+            tile: tile?,
+            minimap: minimap?,
+            hud: hud?,
+            text: text?,
+            overlay: overlay?,
+            watch: None,
+        })
+    }
+
+    /// Builds the shaders in `dir`, falling back to the bundled ones, and
+    /// watches it for changes.
+    pub fn watch(dir: &Path) -> Result<Self, ShaderError> {
+        info!("Watching shaders in {}", dir.display());
+
+        let mut shaders = Self::new()?;
+        shaders.watch = Some(Watch {
+            dir: dir.to_owned(),
+            modified: [None; 5],
+            polled: Instant::now(),
+        });
+        shaders.rebuild_changed();
+
+        Ok(shaders)
+    }
+
+    /// Rebuilds the watched programs whose files changed. Does nothing
+    /// unless watching, or more often than `POLL_INTERVAL`.
+    pub fn reload(&mut self) {
+        match &mut self.watch {
+            Some(watch) if watch.polled.elapsed() >= POLL_INTERVAL => {
+                watch.polled = Instant::now();
+            }
+            _ => return,
+        }
+
+        self.rebuild_changed();
+    }
+
+    fn rebuild_changed(&mut self) {
+        let watch = match &mut self.watch {
+            Some(watch) => watch,
+            None => return,
+        };
+        let programs = [
+            &mut self.tile,
+            &mut self.minimap,
+            &mut self.hud,
+            &mut self.text,
+            &mut self.overlay,
+        ];
+
+        for ((name, program), modified) in
+            NAMES.iter().zip(programs).zip(&mut watch.modified)
+        {
+            let paths = ["vs", "fs"]
+                .map(|stage| watch.dir.join(name).with_extension(stage));
+            let latest = paths
+                .iter()
+                .filter_map(|path| fs::metadata(path).ok()?.modified().ok())
+                .max();
+            if latest.is_none() || latest == *modified {
+                continue;
+            }
+            *modified = latest;
+
+            match build(&paths[0], &paths[1]) {
+                Ok(shader) => {
+                    info!("Rebuilt the {} shader", name);
+                    *program = shader;
+                }
+                Err(message) => {
+                    error!(
+                        "Keeping the last working {} shader: {}",
+                        name, message
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn build(vertex_path: &Path, fragment_path: &Path) -> Result<Shader, String> {
+    let read = |path: &Path| {
+        fs::read_to_string(path)
+            .map_err(|error| format!("{}: {}", path.display(), error))
+    };
+
+    Shader::new(&read(vertex_path)?, &read(fragment_path)?)
+        .map_err(|error| error.to_string())
+}
@@ -0,0 +1,231 @@
+use bytemuck::{Pod, Zeroable};
+
+use crate::graphics::{
+    buffer::Buffer,
+    gl_wrapper::{Attribute, Vertex, VAO},
+    layout::Rect,
+    shader::Shader,
+    texture::Texture,
+};
+
+/// The texture unit the glyph atlas stays bound to, next to the tile
+/// textures on unit 0.
+pub const FONT_TEXTURE_UNIT: u32 = 1;
+
+// `textures/font.png` is a signed distance field of DejaVu Sans Mono, one
+// cell per printable ASCII character from the space, 16 cells per row. All
+// the metrics below are in pixels of the atlas.
+const FIRST_CHAR: char = ' ';
+const LAST_CHAR: char = '~';
+const COLUMNS: usize = 16;
+const ROWS: usize = 6;
+const CELL_WIDTH: f32 = 32.0;
+const CELL_HEIGHT: f32 = 56.0;
+/// Height of a line, which is the font size the atlas is drawn at.
+const LINE_HEIGHT: f32 = 48.0;
+/// From the top of a cell to the top of its line.
+const LINE_TOP: f32 = 4.0;
+/// From the left of a cell to where the glyph's advance starts.
+const GLYPH_LEFT: f32 = 4.62;
+const ADVANCE: f32 = 22.76;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Pod, Zeroable)]
+struct GlyphVertex {
+    position: [f32; 2],
+    tex_coords: [f32; 2],
+    colour: [f32; 4],
+}
+
+impl Vertex for GlyphVertex {
+    fn attributes() -> Vec<Attribute> {
+        vec![
+            Attribute::of(0, |vertex: &Self| &vertex.position),
+            Attribute::of(1, |vertex: &Self| &vertex.tex_coords),
+            Attribute::of(2, |vertex: &Self| &vertex.colour),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Align {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    /// Height of a line, in pixels.
+    pub size: f32,
+    pub colour: [f32; 4],
+    /// How lines are placed around the position they're queued at.
+    pub align: Align,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            size: 24.0,
+            colour: [1.0, 1.0, 1.0, 1.0],
+            align: Align::Left,
+        }
+    }
+}
+
+/// Draws text from the bundled glyph atlas, with `shaders/text.vs` and
+/// `shaders/text.fs`.
+///
+/// Text is queued in pixels of the viewport it will be drawn in, from its
+/// bottom left, and everything queued is drawn at once by `draw`. Since the
+/// atlas holds distance fields, glyphs stay sharp at any size.
+pub struct TextRenderer {
+    vao: VAO,
+    vbo: Buffer<GlyphVertex>,
+    _atlas: Texture,
+    vertices: Vec<GlyphVertex>,
+}
+
+#[allow(clippy::new_without_default)]
+impl TextRenderer {
+    pub fn new() -> Self {
+        let image =
+            image::load_from_memory(include_bytes!("../../textures/font.png"))
+                .expect("Failed to load the font atlas.")
+                .into_luma8();
+        let mut atlas = Texture::new_r8(
+            &image,
+            image.width() as i32,
+            image.height() as i32,
+            FONT_TEXTURE_UNIT,
+        );
+        atlas.bind(FONT_TEXTURE_UNIT);
+
+        let vao = VAO::new();
+        let vbo = Buffer::new(gl::ARRAY_BUFFER, gl::DYNAMIC_DRAW);
+        vao.attach(&vbo);
+        vao.unbind();
+        vbo.unbind();
+
+        TextRenderer {
+            vao,
+            vbo,
+            _atlas: atlas,
+            vertices: Vec::new(),
+        }
+    }
+
+    /// Queues `text` with the top of its first line at `position`. Lines are
+    /// split on `\n`, and characters missing from the atlas are drawn as
+    /// `?`.
+    pub fn queue(
+        &mut self,
+        text: &str,
+        position: (f32, f32),
+        style: &TextStyle,
+    ) {
+        let scale = style.size / LINE_HEIGHT;
+
+        for (line_index, line) in text.lines().enumerate() {
+            let width = measure(line, style.size).0;
+            let mut x = match style.align {
+                Align::Left => position.0,
+                Align::Center => position.0 - width / 2.0,
+                Align::Right => position.0 - width,
+            };
+            let top = position.1 - line_index as f32 * style.size;
+
+            for c in line.chars() {
+                if c != ' ' {
+                    self.queue_glyph(c, (x, top), scale, style.colour);
+                }
+                x += ADVANCE * scale;
+            }
+        }
+    }
+
+    fn queue_glyph(
+        &mut self,
+        c: char,
+        (x, top): (f32, f32),
+        scale: f32,
+        colour: [f32; 4],
+    ) {
+        let c = if (FIRST_CHAR..=LAST_CHAR).contains(&c) {
+            c
+        } else {
+            '?'
+        };
+        let index = c as usize - FIRST_CHAR as usize;
+        let (atlas_width, atlas_height) = atlas_size();
+
+        let left = x - GLYPH_LEFT * scale;
+        let right = left + CELL_WIDTH * scale;
+        let top = top + LINE_TOP * scale;
+        let bottom = top - CELL_HEIGHT * scale;
+
+        // The texture starts with the top row of the image, so v grows
+        // downwards.
+        let u0 = (index % COLUMNS) as f32 * CELL_WIDTH / atlas_width;
+        let u1 = u0 + CELL_WIDTH / atlas_width;
+        let v0 = (index / COLUMNS) as f32 * CELL_HEIGHT / atlas_height;
+        let v1 = v0 + CELL_HEIGHT / atlas_height;
+
+        for (x, y, u, v) in [
+            (left, top, u0, v0),
+            (right, top, u1, v0),
+            (left, bottom, u0, v1),
+            (right, top, u1, v0),
+            (left, bottom, u0, v1),
+            (right, bottom, u1, v1),
+        ] {
+            self.vertices.push(GlyphVertex {
+                position: [x, y],
+                tex_coords: [u, v],
+                colour,
+            });
+        }
+    }
+
+    /// Draws and clears everything queued, in the viewport `area` is set as.
+    pub fn draw(&mut self, shader: &Shader, area: &Rect) {
+        if self.vertices.is_empty() {
+            return;
+        }
+
+        shader.use_program();
+        shader
+            .set_vec2("viewportSize", [area.width as f32, area.height as f32]);
+        shader.set_sampler("fontAtlas", FONT_TEXTURE_UNIT);
+
+        self.vbo.set_data(&self.vertices);
+        self.vao.bind();
+        unsafe {
+            // Glyphs overlap and are drawn over everything else.
+            gl::Disable(gl::DEPTH_TEST);
+            gl::DrawArrays(gl::TRIANGLES, 0, self.vertices.len() as i32);
+            gl::Enable(gl::DEPTH_TEST);
+        }
+        self.vao.unbind();
+        self.vbo.unbind();
+
+        self.vertices.clear();
+    }
+}
+
+/// The width and height of `text` drawn with lines `size` pixels high.
+pub fn measure(text: &str, size: f32) -> (f32, f32) {
+    let scale = size / LINE_HEIGHT;
+    let columns = text.lines().map(|line| line.chars().count()).max();
+    let lines = text.lines().count();
+
+    (
+        columns.unwrap_or(0) as f32 * ADVANCE * scale,
+        lines as f32 * size,
+    )
+}
+
+fn atlas_size() -> (f32, f32) {
+    (COLUMNS as f32 * CELL_WIDTH, ROWS as f32 * CELL_HEIGHT)
+}
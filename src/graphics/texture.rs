@@ -1,12 +1,50 @@
+use gl::types::GLenum;
 use log::info;
 
+use crate::graphics::{
+    debug,
+    theme::{TileArt, TILES},
+};
+
+/// How the tile texture is sampled between texels and across mipmaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Filter {
+    /// Sharp texels, for pixel art.
+    Nearest,
+    /// Blended texels, from the nearest mipmap.
+    Linear,
+    /// Blended texels, blended between mipmaps.
+    #[default]
+    Trilinear,
+}
+
+impl Filter {
+    /// The minifying and magnifying filters.
+    fn parameters(self) -> (GLenum, GLenum) {
+        match self {
+            Filter::Nearest => (gl::NEAREST_MIPMAP_NEAREST, gl::NEAREST),
+            Filter::Linear => (gl::LINEAR_MIPMAP_NEAREST, gl::LINEAR),
+            Filter::Trilinear => (gl::LINEAR_MIPMAP_LINEAR, gl::LINEAR),
+        }
+    }
+}
+
 pub struct Texture {
     id: gl::types::GLuint,
+    target: gl::types::GLenum,
     unit: Option<u32>,
 }
 
 impl Texture {
-    pub fn new(image_file: Vec<u8>, unit: u32) -> Self {
+    /// The tile texture array, one layer per tile state of `art`, with a
+    /// full chain of mipmaps. Its storage can't change size, so a new theme
+    /// gets a new texture.
+    pub fn tiles(art: &TileArt, filter: Filter, unit: u32) -> Self {
+        let size = art.size as i32;
+        let layers = TILES.len() as i32;
+        let levels = (u32::BITS - art.size.leading_zeros()) as i32;
+        assert_eq!(art.pixels.len(), (size * size * 4 * layers) as usize);
+
         let mut id = 0;
 
         unsafe {
@@ -17,11 +55,19 @@ impl Texture {
 
         let mut texture = Texture {
             id,
+            target: gl::TEXTURE_2D_ARRAY,
             unit: Some(unit),
         };
 
         unsafe {
-            gl::TexStorage3D(gl::TEXTURE_2D_ARRAY, 3, gl::RGB8, 32, 32, 14);
+            gl::TexStorage3D(
+                gl::TEXTURE_2D_ARRAY,
+                levels,
+                gl::RGBA8,
+                size,
+                size,
+                layers,
+            );
 
             gl::TexParameteri(
                 gl::TEXTURE_2D_ARRAY,
@@ -33,52 +79,109 @@ impl Texture {
                 gl::TEXTURE_WRAP_T,
                 gl::REPEAT as i32,
             );
-            gl::TexParameteri(
-                gl::TEXTURE_2D_ARRAY,
-                gl::TEXTURE_MIN_FILTER,
-                gl::LINEAR as i32,
-            );
-            gl::TexParameteri(
+
+            gl::TexSubImage3D(
                 gl::TEXTURE_2D_ARRAY,
-                gl::TEXTURE_MAG_FILTER,
-                gl::LINEAR as i32,
+                0,
+                0,
+                0,
+                0,
+                size,
+                size,
+                layers,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                art.pixels.as_ptr() as *const _,
             );
+            // Each layer is scaled down on its own, so tiles don't bleed
+            // into each other.
+            gl::GenerateMipmap(gl::TEXTURE_2D_ARRAY);
+        }
+        debug::check("Texture::tiles");
+        texture.set_filter(filter);
+
+        texture.unbind();
+
+        texture
+    }
+
+    /// A single channel 2D texture, sampled linearly without mipmaps, like
+    /// the glyph atlas of the text renderer.
+    pub fn new_r8(pixels: &[u8], width: i32, height: i32, unit: u32) -> Self {
+        assert_eq!(pixels.len(), (width * height) as usize);
 
-            for i in 0..14 {
-                gl::TexSubImage3D(
-                    gl::TEXTURE_2D_ARRAY,
-                    0,
-                    0,
-                    0,
-                    i,
-                    32,
-                    32,
-                    1,
-                    gl::RGB,
-                    gl::UNSIGNED_BYTE,
-                    image_file.as_ptr().add((i * 32 * 32 * 3) as usize)
-                        as *const _,
-                );
+        let mut id = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut id);
+            gl::ActiveTexture(gl::TEXTURE0 + unit);
+            gl::BindTexture(gl::TEXTURE_2D, id);
+        }
+
+        let mut texture = Texture {
+            id,
+            target: gl::TEXTURE_2D,
+            unit: Some(unit),
+        };
+
+        unsafe {
+            gl::TexStorage2D(gl::TEXTURE_2D, 1, gl::R8, width, height);
+
+            for (parameter, value) in [
+                (gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE),
+                (gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE),
+                (gl::TEXTURE_MIN_FILTER, gl::LINEAR),
+                (gl::TEXTURE_MAG_FILTER, gl::LINEAR),
+            ] {
+                gl::TexParameteri(gl::TEXTURE_2D, parameter, value as i32);
             }
+
+            // Rows of single bytes aren't 4-byte aligned in general.
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexSubImage2D(
+                gl::TEXTURE_2D,
+                0,
+                0,
+                0,
+                width,
+                height,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as *const _,
+            );
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
         }
+        debug::check("Texture::new_r8");
 
         texture.unbind();
 
         texture
     }
 
+    /// Changes how the texture is sampled. It must be bound.
+    pub fn set_filter(&self, filter: Filter) {
+        let (min, mag) = filter.parameters();
+
+        unsafe {
+            gl::TexParameteri(self.target, gl::TEXTURE_MIN_FILTER, min as i32);
+            gl::TexParameteri(self.target, gl::TEXTURE_MAG_FILTER, mag as i32);
+        }
+        debug::check("Texture::set_filter");
+    }
+
     pub fn bind(&mut self, unit: u32) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0 + unit);
-            gl::BindTexture(gl::TEXTURE_2D_ARRAY, self.id);
+            gl::BindTexture(self.target, self.id);
             self.unit = Some(unit);
         }
+        debug::check("Texture::bind");
     }
 
     pub fn unbind(&mut self) {
         unsafe {
             gl::ActiveTexture(gl::TEXTURE0 + self.unit.unwrap());
-            gl::BindTexture(gl::TEXTURE_2D_ARRAY, 0);
+            gl::BindTexture(self.target, 0);
             self.unit = None;
         }
     }
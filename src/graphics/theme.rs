@@ -0,0 +1,334 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+use log::{info, warn};
+use toml::{Table, Value};
+
+use crate::config::{self, Config};
+
+/// The art of a theme, one image per tile state in the order of the layers
+/// of the tile texture. The names are the file names of a theme directory,
+/// without the `.png`.
+pub const TILES: [&str; 14] = [
+    "tile_unrevealed",
+    "flag",
+    "tile_revealed_0",
+    "tile_revealed_1",
+    "tile_revealed_2",
+    "tile_revealed_3",
+    "tile_revealed_4",
+    "tile_revealed_5",
+    "tile_revealed_6",
+    "tile_revealed_7",
+    "tile_revealed_8",
+    "mine_revealed",
+    "mine_exploded",
+    "flag_wrong",
+];
+
+/// Largest side of a tile, in pixels.
+pub const MAX_TILE_SIZE: u32 = 1024;
+
+/// The file read from a theme directory, when it has one.
+const MANIFEST: &str = "theme.toml";
+
+/// Background colours of the built-in themes besides the default one, which
+/// uses the background from the config.
+const BUILTIN: [(&str, [f32; 4]); 2] = [
+    ("Dark", [0.08, 0.08, 0.1, 1.0]),
+    ("Light", [0.8, 0.8, 0.78, 1.0]),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    /// The bundled art, with its own background or the config's.
+    Builtin(Option<[f32; 4]>),
+    /// A folder of PNGs named after `TILES`, with an optional `theme.toml`.
+    Directory(PathBuf),
+    /// A manifest, with the art it names next to it.
+    Manifest(PathBuf),
+}
+
+/// A theme that can be picked. Nothing is read until it is loaded, so
+/// changes to its files show the next time it is picked.
+///
+/// Themes are folders and manifests in the `themes` folder next to the
+/// config. A folder holds one PNG per tile state, named after `TILES`, and
+/// optionally a `theme.toml` manifest, which can also stand alone:
+///
+/// ```toml
+/// background = [0.1, 0.1, 0.1]
+///
+/// # A single image of square cells, read row by row in the order of
+/// # `TILES`, instead of one file per tile state.
+/// atlas = "atlas.png"
+/// columns = 4
+///
+/// # Art for any tile state, overriding the default: a file, or the index
+/// # of a cell of the atlas.
+/// [tiles]
+/// flag = "red_flag.png"
+/// flag_wrong = 15
+/// ```
+///
+/// Tiles can have any resolution. All of them are scaled to the size of
+/// the unrevealed tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    source: Source,
+}
+
+impl Theme {
+    pub fn dir() -> Option<PathBuf> {
+        Config::path()
+            .and_then(|path| path.parent().map(|dir| dir.join("themes")))
+    }
+
+    /// The built-in themes, then those in `Theme::dir` by name.
+    pub fn available() -> Vec<Theme> {
+        let mut themes = vec![Theme::default()];
+        themes.extend(BUILTIN.iter().map(|&(name, background)| Theme {
+            name: name.to_owned(),
+            source: Source::Builtin(Some(background)),
+        }));
+
+        let entries = match Self::dir().map(fs::read_dir) {
+            Some(Ok(entries)) => entries,
+            _ => return themes,
+        };
+        let mut found: Vec<_> = entries
+            .filter_map(|entry| Self::from_path(&entry.ok()?.path()))
+            .collect();
+        found.sort_by(|a, b| a.name.cmp(&b.name));
+        themes.extend(found);
+
+        themes
+    }
+
+    /// The theme in the folder or manifest at `path`, if it is one.
+    pub fn from_path(path: &Path) -> Option<Theme> {
+        let source = if path.is_dir() {
+            Source::Directory(path.to_owned())
+        } else if path.extension() == Some("toml".as_ref()) {
+            Source::Manifest(path.to_owned())
+        } else {
+            return None;
+        };
+
+        Some(Theme {
+            name: path.file_stem()?.to_string_lossy().into_owned(),
+            source,
+        })
+    }
+
+    /// Finds the theme the config asks for, by name or by path, falling
+    /// back to the default. Themes from elsewhere are added to `themes`.
+    pub fn find(themes: &mut Vec<Theme>, name: &str) -> usize {
+        if let Some(index) = themes
+            .iter()
+            .position(|theme| theme.name.eq_ignore_ascii_case(name))
+        {
+            return index;
+        }
+
+        match Self::from_path(Path::new(name)) {
+            Some(theme) => {
+                themes.push(theme);
+                themes.len() - 1
+            }
+            None => {
+                warn!("Unknown theme `{}`, using the default", name);
+                0
+            }
+        }
+    }
+
+    pub fn load(&self) -> Result<TileArt, String> {
+        info!("Loading theme `{}`", self.name);
+
+        match &self.source {
+            Source::Builtin(background) => {
+                let atlas = image::load_from_memory(include_bytes!(
+                    "../../textures/atlas.png"
+                ))
+                .map_err(|error| error.to_string())?;
+                let mut art = TileArt::from_atlas(
+                    &atlas,
+                    4,
+                    Path::new("."),
+                    &Table::new(),
+                )?;
+                art.background = *background;
+                Ok(art)
+            }
+            Source::Directory(dir) => match dir.join(MANIFEST) {
+                manifest if manifest.exists() => {
+                    TileArt::from_manifest(&manifest)
+                }
+                _ => TileArt::from_files(dir, &Table::new()),
+            },
+            Source::Manifest(manifest) => TileArt::from_manifest(manifest),
+        }
+    }
+}
+
+impl Default for Theme {
+    /// The bundled art, on the background from the config.
+    fn default() -> Self {
+        Theme {
+            name: "Default".to_owned(),
+            source: Source::Builtin(None),
+        }
+    }
+}
+
+/// The loaded art of a theme, ready for `Texture::tiles`.
+#[derive(Debug, Clone)]
+pub struct TileArt {
+    /// Side of each tile, in pixels.
+    pub size: u32,
+    /// RGBA pixels of each tile in turn, in the order of `TILES`.
+    pub pixels: Vec<u8>,
+    /// The background to draw the board on, instead of the config's.
+    pub background: Option<[f32; 4]>,
+}
+
+impl TileArt {
+    fn from_manifest(path: &Path) -> Result<TileArt, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+        let manifest = text
+            .parse::<Table>()
+            .map_err(|error| format!("{}: {}", path.display(), error))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let tiles = match manifest.get("tiles") {
+            Some(Value::Table(tiles)) => tiles.clone(),
+            Some(_) => return Err("`tiles` must be a table".to_owned()),
+            None => Table::new(),
+        };
+
+        let mut art = match manifest.get("atlas") {
+            Some(Value::String(file)) => {
+                let atlas = open(&dir.join(file))?;
+                let columns = match manifest.get("columns") {
+                    Some(Value::Integer(n)) if *n > 0 => *n as u32,
+                    Some(_) => {
+                        return Err(
+                            "`columns` must be a positive number".to_owned()
+                        )
+                    }
+                    None => TILES.len() as u32,
+                };
+                TileArt::from_atlas(&atlas, columns, dir, &tiles)?
+            }
+            Some(_) => return Err("`atlas` must be a file name".to_owned()),
+            None => TileArt::from_files(dir, &tiles)?,
+        };
+
+        if let Some(background) = manifest.get("background") {
+            art.background = Some(
+                config::colour(background)
+                    .map_err(|message| format!("`background`: {}", message))?,
+            );
+        }
+
+        Ok(art)
+    }
+
+    /// One file per tile, named after `TILES` unless `tiles` names another.
+    fn from_files(dir: &Path, tiles: &Table) -> Result<TileArt, String> {
+        let images = TILES
+            .iter()
+            .map(|&name| match tiles.get(name) {
+                Some(Value::String(file)) => open(&dir.join(file)),
+                Some(_) => Err(format!("`tiles.{}` must be a file name", name)),
+                None => open(&dir.join(name).with_extension("png")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        TileArt::from_images(images)
+    }
+
+    /// Cells of `atlas`, `columns` to a row, in the order of `TILES` unless
+    /// `tiles` gives another cell or a file in `dir`.
+    fn from_atlas(
+        atlas: &DynamicImage,
+        columns: u32,
+        dir: &Path,
+        tiles: &Table,
+    ) -> Result<TileArt, String> {
+        let size = atlas.width() / columns;
+        let rows = atlas.height() / size.max(1);
+        let cells = columns * rows;
+        if size == 0 || cells == 0 {
+            return Err("the atlas is smaller than a row of cells".to_owned());
+        }
+
+        let images = TILES
+            .iter()
+            .enumerate()
+            .map(|(index, &name)| {
+                let cell = match tiles.get(name) {
+                    Some(Value::String(file)) => return open(&dir.join(file)),
+                    Some(Value::Integer(cell)) => u32::try_from(*cell).ok(),
+                    Some(_) => None,
+                    None => Some(index as u32),
+                };
+
+                match cell {
+                    Some(cell) if cell < cells => {
+                        let (x, y) =
+                            (cell % columns * size, cell / columns * size);
+                        Ok(atlas.crop_imm(x, y, size, size))
+                    }
+                    _ => Err(format!(
+                        "`tiles.{}` must be a file or a cell from 0 to {}",
+                        name,
+                        cells - 1
+                    )),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        TileArt::from_images(images)
+    }
+
+    fn from_images(images: Vec<DynamicImage>) -> Result<TileArt, String> {
+        let size = images[0].width();
+        if !(1..=MAX_TILE_SIZE).contains(&size) {
+            return Err(format!(
+                "tiles must be between 1 and {} pixels wide",
+                MAX_TILE_SIZE
+            ));
+        }
+
+        let mut pixels =
+            Vec::with_capacity((size * size * 4) as usize * TILES.len());
+        for (image, name) in images.into_iter().zip(TILES) {
+            let image: RgbaImage =
+                if (image.width(), image.height()) == (size, size) {
+                    image.into_rgba8()
+                } else {
+                    info!("Scaling `{}` to {}x{}", name, size, size);
+                    image
+                        .resize_exact(size, size, FilterType::Triangle)
+                        .into_rgba8()
+                };
+            pixels.extend_from_slice(&image);
+        }
+
+        Ok(TileArt {
+            size,
+            pixels,
+            background: None,
+        })
+    }
+}
+
+fn open(path: &Path) -> Result<DynamicImage, String> {
+    image::open(path).map_err(|error| format!("{}: {}", path.display(), error))
+}
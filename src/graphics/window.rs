@@ -1,18 +1,62 @@
-use glfw::{Action, Context, Key, WindowEvent};
-use log::info;
+use glfw::{Action, Context, WindowEvent};
+use log::{info, warn};
 use std::sync::mpsc::Receiver;
 
-use crate::game::{Game, GameState};
+use crate::{
+    config::Config,
+    game::{Game, GameState},
+    graphics::{
+        camera::{self, Camera},
+        debug::{self, DebugMode},
+        gamepad::Gamepad,
+        hud,
+        input::{self, Input},
+        layout::Layout,
+        menu::{self, Menu},
+        minimap,
+    },
+    statistics::Statistics,
+};
+
+/// How much one notch of the mouse wheel or a zoom key zooms.
+const ZOOM_STEP: f64 = 1.25;
+/// How far a pan key moves the view: a quarter of the viewport.
+const PAN_STEP: f64 = 0.5;
 
 pub struct Window {
     pub glfw: glfw::Glfw,
     pub window: glfw::Window,
     pub events: Receiver<(f64, WindowEvent)>,
+    pub camera: Camera,
+    pub menu: Menu,
+    pub statistics: Statistics,
+    config: Config,
+    gamepad: Gamepad,
+    /// The mouse button dragging the view, and where the pointer last was.
+    drag: Option<(glfw::MouseButton, f64, f64)>,
+    /// Where the mouse pointer is, unless it left the window.
+    pointer: Option<(f64, f64)>,
+    /// The mouse button held down since it revealed or chorded, whose tiles
+    /// show pressed until it's let go.
+    held: Option<glfw::MouseButton>,
+    /// Whether the last key event was an auto-repeat, whose characters are
+    /// dropped along with it.
+    repeating: bool,
     wireframe: bool,
+    /// Whether the pause screen is up, hiding the board.
+    paused: bool,
+    debug: Option<DebugMode>,
 }
 
 impl Window {
-    pub fn new(width: u32, height: u32, title: &str) -> Window {
+    pub fn new(
+        width: u32,
+        height: u32,
+        title: &str,
+        fullscreen: bool,
+        debug: Option<DebugMode>,
+        config: Config,
+    ) -> Window {
         let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).unwrap();
 
         glfw.window_hint(glfw::WindowHint::ContextVersion(4, 3));
@@ -20,22 +64,60 @@ impl Window {
             glfw::OpenGlProfileHint::Core,
         ));
         glfw.window_hint(glfw::WindowHint::Samples(Some(16)));
+        glfw.window_hint(glfw::WindowHint::OpenGlDebugContext(
+            matches!(debug, Some(mode) if mode.wants_context()),
+        ));
 
         let (mut window, events) = glfw
-            .create_window(width, height, title, glfw::WindowMode::Windowed)
+            .with_primary_monitor(|glfw, monitor| match monitor {
+                Some(monitor) if fullscreen => {
+                    let (width, height) = monitor
+                        .get_video_mode()
+                        .map_or((width, height), |mode| {
+                            (mode.width, mode.height)
+                        });
+                    glfw.create_window(
+                        width,
+                        height,
+                        title,
+                        glfw::WindowMode::FullScreen(monitor),
+                    )
+                }
+                _ => glfw.create_window(
+                    width,
+                    height,
+                    title,
+                    glfw::WindowMode::Windowed,
+                ),
+            })
             .expect("Failed to create GLFW window.");
 
         window.set_key_polling(true);
-        window.set_framebuffer_size_polling(true);
+        window.set_char_polling(true);
         window.set_cursor_pos_polling(true);
+        window.set_cursor_enter_polling(true);
         window.set_cursor_mode(glfw::CursorMode::Normal);
         window.set_mouse_button_polling(true);
+        window.set_scroll_polling(true);
+        window.set_focus_polling(true);
+        window.set_iconify_polling(true);
 
         Window {
             glfw,
             window,
             events,
+            camera: Camera::default(),
+            menu: Menu::new(&config),
+            statistics: Statistics::load(),
+            gamepad: Gamepad::new(&config),
+            config,
+            drag: None,
+            pointer: None,
+            held: None,
+            repeating: false,
             wireframe: false,
+            paused: false,
+            debug,
         }
     }
 
@@ -69,14 +151,11 @@ impl Window {
         gl::load_with(|symbol| {
             self.window.get_proc_address(symbol) as *const _
         });
+        if let Some(mode) = self.debug {
+            debug::init(mode);
+        }
 
         unsafe {
-            gl::Viewport(
-                0,
-                0,
-                self.window.get_framebuffer_size().0,
-                self.window.get_framebuffer_size().1,
-            );
             gl::Enable(gl::DEPTH_TEST);
             gl::Enable(gl::BLEND);
             gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
@@ -89,76 +168,398 @@ impl Window {
     }
 
     pub fn process_events(&mut self, game: &mut Game) {
-        let (width, height) = self.get_framebuffer_size();
+        let events: Vec<_> = glfw::flush_messages(&self.events).collect();
 
-        for (_, event) in glfw::flush_messages(&self.events) {
-            match event {
-                WindowEvent::FramebufferSize(width, height) => unsafe {
-                    let x = (width - height) / 2;
-                    let y = (height - width) / 2;
+        for (_, event) in events {
+            if let WindowEvent::CursorPos(x, y) = event {
+                self.pointer = Some((x, y));
+            }
+            // Characters come right after the key event that typed them.
+            if let WindowEvent::Key(_, _, action, _) = event {
+                self.repeating = action == Action::Repeat;
+            }
 
-                    if width > height {
-                        gl::Viewport(x, 0, height, height);
-                    } else {
-                        gl::Viewport(0, y, width, width);
-                    }
-                },
-                WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
-                    self.window.set_should_close(true)
+            let input = match event {
+                WindowEvent::CursorEnter(false) => {
+                    self.pointer = None;
+                    continue;
                 }
-                WindowEvent::Key(Key::W, _, Action::Press, _) => {
-                    if self.wireframe {
-                        self.wireframe = false;
-                        unsafe {
-                            gl::PolygonMode(gl::FRONT_AND_BACK, gl::FILL);
-                        }
-                    } else {
-                        self.wireframe = true;
-                        unsafe {
-                            gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
-                        }
-                    }
+                // Nobody is watching the board then.
+                WindowEvent::Focus(false) | WindowEvent::Iconify(true) => {
+                    self.pause(game);
+                    continue;
                 }
-                WindowEvent::Key(Key::R, _, Action::Press, _) => {
-                    *game = Game::new(game.width, game.height);
+                WindowEvent::CursorPos(x, y) if self.menu.is_open() => {
+                    let area = self.layout(game).window;
+                    self.menu.select_at(x, y, &area);
+                    continue;
                 }
-                WindowEvent::Key(Key::Space, _, Action::Press, _) => {
-                    let x_px = self.window.get_cursor_pos().0;
-                    let y_px = self.window.get_cursor_pos().1;
-
-                    game.space_click(x_px, y_px, width as f64, height as f64);
+                WindowEvent::Scroll(_, y) if self.menu.is_open() => {
+                    self.menu.scroll(y.signum() as isize);
+                    continue;
                 }
-                WindowEvent::Key(Key::Equal, _, Action::Press, _) => {
-                    game.increase_size();
+                // Held keys repeat in the menu, to step through values, but
+                // holding the key that opened it mustn't close it again.
+                WindowEvent::Key(key, _, Action::Repeat, _)
+                    if self.repeats_in_menu(Input::Key(key)) =>
+                {
+                    Input::Key(key)
                 }
-                WindowEvent::Key(Key::Minus, _, Action::Press, _) => {
-                    game.decrease_size();
+                WindowEvent::Char(c)
+                    if self.repeating
+                        && self.repeats_in_menu(Input::from_char(c)) =>
+                {
+                    Input::from_char(c)
                 }
-                WindowEvent::MouseButton(button, action, _) => match button {
-                    glfw::MouseButtonLeft => {
-                        if action == glfw::Action::Press {
-                            let x = self.window.get_cursor_pos().0;
-                            let y = self.window.get_cursor_pos().1;
-
-                            game.left_click(x, y, width as f64, height as f64);
-                            info!("Clicked tile at ({}, {})", x, y);
+                WindowEvent::CursorPos(x, y) => {
+                    game.cursor = None;
+                    self.drag_to(x, y, game);
+                    continue;
+                }
+                WindowEvent::Scroll(_, y) => {
+                    let point = self.pointer_position(game);
+                    self.camera.zoom_at(
+                        ZOOM_STEP.powf(y),
+                        point,
+                        game.width,
+                        game.height,
+                    );
+                    continue;
+                }
+                WindowEvent::Key(key, _, Action::Press, _) => Input::Key(key),
+                WindowEvent::Char(_) if self.repeating => continue,
+                WindowEvent::Char(c) => Input::from_char(c),
+                WindowEvent::MouseButton(button, Action::Press, _) => {
+                    Input::Mouse(button)
+                }
+                WindowEvent::MouseButton(button, Action::Release, _) => {
+                    if let Some((dragging, ..)) = self.drag {
+                        if dragging == button {
+                            self.drag = None;
                         }
                     }
-                    glfw::MouseButtonRight => {
-                        if action == glfw::Action::Press {
-                            let x = self.window.get_cursor_pos().0;
-                            let y = self.window.get_cursor_pos().1;
-
-                            game.right_click(x, y, width as f64, height as f64);
-                        }
+                    if self.held == Some(button) {
+                        self.held = None;
                     }
-                    _ => {}
-                },
-                _ => {}
+                    continue;
+                }
+                _ => continue,
+            };
+
+            self.handle_input(input, game);
+        }
+    }
+
+    /// Whether `input` acts again while held, which it only does in the
+    /// menu.
+    fn repeats_in_menu(&self, input: Input) -> bool {
+        self.menu.is_open()
+            && !self
+                .config
+                .bindings
+                .actions(input)
+                .contains(&input::Action::Menu)
+    }
+
+    fn poll_gamepad(&mut self, game: &mut Game) {
+        let time = self.get_time();
+
+        for input in self.gamepad.poll(&self.glfw, time) {
+            self.handle_input(input, game);
+        }
+    }
+
+    fn handle_input(&mut self, input: Input, game: &mut Game) {
+        if self.menu.is_open() {
+            self.handle_menu_input(input, game);
+            return;
+        }
+        if self.paused {
+            self.handle_paused_input(input, game);
+            return;
+        }
+
+        if let Input::Mouse(_) = input {
+            if self.click_face(game) {
+                game.restart();
+                return;
+            }
+            if self.click_minimap(game) {
+                return;
+            }
+        }
+
+        match input {
+            Input::Mouse(_) => game.cursor = None,
+            // There is no pointer to aim with a gamepad.
+            Input::Gamepad(_) if game.cursor.is_none() => {
+                game.cursor = Some(self.centre_tile(game));
+            }
+            _ => (),
+        }
+
+        for action in self.config.bindings.actions(input) {
+            // Mouse reveals and chords act on the press, and the tiles the
+            // button would press show sunken while it's held.
+            if let (
+                Input::Mouse(button),
+                input::Action::Reveal | input::Action::Chord,
+            ) = (input, action)
+            {
+                self.held = Some(button);
+            }
+
+            self.perform(action, input, game);
+        }
+        self.statistics.update(game);
+    }
+
+    fn handle_menu_input(&mut self, input: Input, game: &mut Game) {
+        if let Input::Mouse(_) = input {
+            let (x_px, y_px) = self.window.get_cursor_pos();
+            let area = self.layout(game).window;
+
+            // Clicks only act on the item they land on.
+            if !self.menu.select_at(x_px, y_px, &area) {
+                return;
+            }
+        }
+
+        for action in self.config.bindings.actions(input) {
+            match self.menu.handle(action, game) {
+                Some(menu::Command::NewGame) => self.camera.fit(),
+                Some(menu::Command::Quit) => self.window.set_should_close(true),
+                None => (),
+            }
+        }
+    }
+
+    /// Only resumes the game, or opens the menu over the pause screen.
+    fn handle_paused_input(&mut self, input: Input, game: &mut Game) {
+        for action in self.config.bindings.actions(input) {
+            match action {
+                input::Action::Pause | input::Action::Reveal => {
+                    self.paused = false;
+                }
+                input::Action::Menu => self.menu.open(game),
+                input::Action::Quit => self.window.set_should_close(true),
+                _ => (),
+            }
+        }
+    }
+
+    fn perform(
+        &mut self,
+        action: input::Action,
+        input: Input,
+        game: &mut Game,
+    ) {
+        let (x, y) = match game.cursor {
+            Some(cursor) => cursor,
+            None => {
+                let (width, height) = self.get_framebuffer_size();
+                let (x_px, y_px) = self.window.get_cursor_pos();
+
+                game.tile_at(
+                    x_px,
+                    y_px,
+                    width as f64,
+                    height as f64,
+                    &self.camera,
+                )
+            }
+        };
+
+        match action {
+            input::Action::Reveal => {
+                game.reveal_at(x, y);
+                info!("Revealed tile at ({}, {})", x, y);
+            }
+            input::Action::Flag => game.flag_at(x, y),
+            input::Action::Chord => game.chord_or_flag_at(x, y),
+            input::Action::CursorUp => self.move_cursor(game, 0, 1),
+            input::Action::CursorDown => self.move_cursor(game, 0, -1),
+            input::Action::CursorLeft => self.move_cursor(game, -1, 0),
+            input::Action::CursorRight => self.move_cursor(game, 1, 0),
+            input::Action::Pan => {
+                if let Input::Mouse(button) = input {
+                    let (x_px, y_px) = self.window.get_cursor_pos();
+                    self.drag = Some((button, x_px, y_px));
+                }
+            }
+            input::Action::PanUp => self.camera.pan((0.0, -PAN_STEP)),
+            input::Action::PanDown => self.camera.pan((0.0, PAN_STEP)),
+            input::Action::PanLeft => self.camera.pan((PAN_STEP, 0.0)),
+            input::Action::PanRight => self.camera.pan((-PAN_STEP, 0.0)),
+            input::Action::ZoomIn => self.zoom(ZOOM_STEP, game),
+            input::Action::ZoomOut => self.zoom(1.0 / ZOOM_STEP, game),
+            input::Action::FitBoard => self.camera.fit(),
+            input::Action::Restart => game.restart(),
+            input::Action::IncreaseSize => {
+                game.increase_size(
+                    self.config.width_increment,
+                    self.config.height_increment,
+                );
+                self.camera.fit();
+            }
+            input::Action::DecreaseSize => {
+                game.decrease_size(
+                    self.config.width_increment,
+                    self.config.height_increment,
+                );
+                self.camera.fit();
+            }
+            input::Action::ToggleWireframe => {
+                self.set_wireframe_mode(!self.wireframe);
+            }
+            input::Action::Save => save(game),
+            input::Action::Pause => self.pause(game),
+            input::Action::Menu => {
+                self.drag = None;
+                self.held = None;
+                self.menu.open(game);
             }
+            input::Action::Quit => self.window.set_should_close(true),
         }
     }
 
+    /// Shows the pause screen, if there is a game in progress to pause.
+    fn pause(&mut self, game: &Game) {
+        if matches!(game.state, GameState::Playing(_)) {
+            self.paused = true;
+            self.drag = None;
+            self.held = None;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Shows which tile the pointer is over, and which tiles the held mouse
+    /// button would press.
+    fn update_preview(&self, game: &mut Game) {
+        game.hovered = None;
+        game.pressed.clear();
+
+        let (x_px, y_px) = match self.pointer {
+            Some(pointer) if !self.menu.is_open() && !self.paused => pointer,
+            _ => return,
+        };
+        if game.cursor.is_some()
+            || (self.camera.is_zoomed()
+                && minimap::board_position(self.pointer_position(game))
+                    .is_some())
+        {
+            return;
+        }
+
+        let (width, height) = self.get_framebuffer_size();
+        let (x, y) =
+            game.tile_at(x_px, y_px, width as f64, height as f64, &self.camera);
+        if !game.contains(x, y) {
+            return;
+        }
+
+        game.hovered = Some((x, y));
+        if let Some(button) = self.held {
+            let chord = self
+                .config
+                .bindings
+                .actions(Input::Mouse(button))
+                .contains(&input::Action::Chord);
+
+            game.pressed = game.press_preview(x, y, chord);
+        }
+    }
+
+    fn click_face(&self, game: &Game) -> bool {
+        let (_, height) = self.get_framebuffer_size();
+        let (x_px, y_px) = self.window.get_cursor_pos();
+
+        hud::face_contains(&self.layout(game).hud, x_px, y_px, height as f64)
+    }
+
+    /// Jumps the camera to the spot clicked on the minimap, if the pointer
+    /// is over it.
+    fn click_minimap(&mut self, game: &Game) -> bool {
+        if !self.camera.is_zoomed() {
+            return false;
+        }
+
+        match minimap::board_position(self.pointer_position(game)) {
+            Some(position) => {
+                self.camera.look_at(position);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the keyboard cursor, or shows it in the middle of the view,
+    /// and pans to keep it visible.
+    fn move_cursor(&mut self, game: &mut Game, dx: isize, dy: isize) {
+        match game.cursor {
+            Some(_) => game.move_cursor(dx, dy),
+            None => game.cursor = Some(self.centre_tile(game)),
+        }
+
+        if let Some((x, y)) = game.cursor {
+            self.camera.show_tile(x, y, game.width, game.height);
+        }
+    }
+
+    fn centre_tile(&self, game: &Game) -> (isize, isize) {
+        let (width, height) = self.get_framebuffer_size();
+        let (width, height) = (width as f64, height as f64);
+
+        game.tile_at(width / 2.0, height / 2.0, width, height, &self.camera)
+    }
+
+    /// Zooms on the middle of the view, keeping the keyboard cursor visible.
+    fn zoom(&mut self, factor: f64, game: &Game) {
+        self.camera
+            .zoom_at(factor, (0.0, 0.0), game.width, game.height);
+
+        if let Some((x, y)) = game.cursor {
+            self.camera.show_tile(x, y, game.width, game.height);
+        }
+    }
+
+    /// The mouse pointer in normalized device coordinates.
+    fn pointer_position(&self, game: &Game) -> (f64, f64) {
+        let (width, height) = self.get_framebuffer_size();
+        let (x_px, y_px) = self.window.get_cursor_pos();
+
+        camera::viewport_position(
+            x_px,
+            y_px,
+            width as f64,
+            height as f64,
+            game.width,
+            game.height,
+        )
+    }
+
+    fn drag_to(&mut self, x_px: f64, y_px: f64, game: &Game) {
+        if let Some((button, last_x, last_y)) = self.drag {
+            let board = self.layout(game).board;
+
+            self.camera.pan((
+                (x_px - last_x) / board.width * 2.0,
+                (last_y - y_px) / board.height * 2.0,
+            ));
+            self.drag = Some((button, x_px, y_px));
+        }
+    }
+
+    /// Where the HUD and `game`'s board go in the window.
+    pub fn layout(&self, game: &Game) -> Layout {
+        let (width, height) = self.get_framebuffer_size();
+
+        Layout::new(width as f64, height as f64, game.width, game.height)
+    }
+
     pub fn get_framebuffer_size(&self) -> (i32, i32) {
         self.window.get_framebuffer_size()
     }
@@ -168,42 +569,25 @@ impl Window {
     }
 
     pub fn update(&mut self, game: &mut Game) {
+        self.statistics.update(game);
         self.process_events(game);
+        self.poll_gamepad(game);
+
+        if !matches!(game.state, GameState::Playing(_)) {
+            self.paused = false;
+        }
+        self.update_preview(game);
+        // The clock only runs while the board can be seen.
+        if self.paused || self.menu.is_open() {
+            game.pause();
+        } else {
+            game.resume();
+        }
+
         self.window.swap_buffers();
         self.glfw.poll_events();
 
-        match game.state {
-            GameState::Won(game_duration) => {
-                let seconds = game_duration.as_secs();
-                let millis = game_duration.subsec_millis();
-                let time = format!("{}.{}", seconds, millis);
-                self.window.set_title(
-                    &("Minesweeper | You won! | You took ".to_owned()
-                        + &*time
-                        + " seconds"),
-                );
-            }
-            GameState::Lost(game_duration) => {
-                let seconds = game_duration.as_secs();
-                let millis = game_duration.subsec_millis();
-                let time = format!("{}.{}", seconds, millis);
-                self.window.set_title(
-                    &("Minesweeper | You lost! | You took ".to_owned()
-                        + &*time
-                        + " seconds"),
-                );
-            }
-            GameState::Playing(_) => {
-                self.window.set_title(&format!(
-                    "Minesweeper | {} mines left | {} seconds",
-                    game.mine_count - game.count_flags(),
-                    game.get_time_since_start().unwrap()
-                ));
-            }
-            GameState::Start => {
-                self.window.set_title("Minesweeper");
-            }
-        }
+        self.window.set_title(&game.status());
 
         unsafe {
             gl::Clear(gl::DEPTH_BUFFER_BIT);
@@ -223,3 +607,23 @@ impl Window {
         }
     }
 }
+
+/// Writes `game` to the save file, once its mines are placed.
+fn save(game: &Game) {
+    if game.state == GameState::Start {
+        info!("Nothing to save before the first reveal");
+        return;
+    }
+    let path = match Game::save_path() {
+        Some(path) => path,
+        None => {
+            warn!("Failed to save the game: no data directory");
+            return;
+        }
+    };
+
+    match game.save(&path) {
+        Ok(()) => info!("Saved the game to {}", path.display()),
+        Err(error) => warn!("Failed to save {}: {}", path.display(), error),
+    }
+}
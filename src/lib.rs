@@ -1,16 +1,23 @@
 use std::ptr;
 
-use graphics::gl_wrapper::VAO;
+use graphics::{debug, gl_wrapper::VAO};
 
+pub mod bot;
+pub mod cli;
+pub mod config;
 pub mod game;
 pub mod graphics;
 pub mod logger;
+pub mod statistics;
 
 pub const DEFAULT_WIDTH: isize = 21;
 pub const DEFAULT_HEIGHT: isize = 21;
+pub const DEFAULT_MINE_DENSITY: f64 = 0.2;
 
-pub const MAX_WIDTH: isize = 950;
-pub const MAX_HEIGHT: isize = 950;
+// A board this size fills the 128 MiB shader storage block every GL 4.3
+// driver supports, at 8 bytes per tile.
+pub const MAX_WIDTH: isize = 4096;
+pub const MAX_HEIGHT: isize = 4096;
 pub const MIN_WIDTH: isize = 1;
 pub const MIN_HEIGHT: isize = 1;
 
@@ -18,7 +25,7 @@ pub const WIDTH_INCREMENT: isize = 5;
 pub const HEIGHT_INCREMENT: isize = 5;
 
 pub const DEFAULT_WINDOW_WIDTH: u32 = 800;
-pub const DEFAULT_WINDOW_HEIGHT: u32 = 800;
+pub const DEFAULT_WINDOW_HEIGHT: u32 = 896;
 
 pub fn clear_draw(r: f32, g: f32, b: f32, a: f32) {
     unsafe {
@@ -32,34 +39,5 @@ pub fn draw_element(vao: &VAO, count: i32) {
     unsafe {
         gl::DrawElements(gl::TRIANGLES, count, gl::UNSIGNED_INT, ptr::null());
     }
-}
-
-pub fn load_images() -> Vec<u8> {
-    let mut files = vec![
-        include_bytes!("../textures/tile_unrevealed.png").to_vec(),
-        include_bytes!("../textures/flag.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_0.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_1.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_2.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_3.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_4.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_5.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_6.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_7.png").to_vec(),
-        include_bytes!("../textures/tile_revealed_8.png").to_vec(),
-        include_bytes!("../textures/mine_revealed.png").to_vec(),
-        include_bytes!("../textures/mine_exploded.png").to_vec(),
-        include_bytes!("../textures/flag_wrong.png").to_vec(),
-    ];
-
-    let mut data: Vec<u8> = Vec::new();
-
-    for file in files.iter_mut() {
-        let image = image::load_from_memory(file)
-            .expect("Failed to load texture image.");
-        let rgb = image.into_rgb8();
-        data.append(&mut rgb.to_vec());
-    }
-
-    data
+    debug::check("draw_element");
 }
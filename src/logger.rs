@@ -1,5 +1,8 @@
 pub fn init() {
-    env_logger::init();
+    env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or("warn"),
+    )
+    .init();
 }
 
 pub use log::*;
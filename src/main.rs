@@ -2,45 +2,197 @@
 
 use glsweeper_rs::{
     clear_draw,
-    game::Game,
-    graphics::{shader::Shader, texture::Texture, window::Window},
+    cli::Options,
+    config::Config,
+    game::{Game, GameState},
+    graphics::{
+        hud::Hud,
+        input::Action,
+        layout::Rect,
+        minimap::Minimap,
+        shaders::Shaders,
+        text::{Align, TextRenderer, TextStyle},
+        texture::Texture,
+        theme::{Theme, TileArt},
+        window::Window,
+    },
     logger,
 };
-use log::debug;
+use log::{debug, warn};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     logger::init();
 
+    let config = Config::load();
+    let options = Options::parse_args(&config);
+    let mut current_game = options.new_game(&config)?;
+
+    let (window_width, window_height) = options.window_size(&config);
     let mut window = Window::new(
-        glsweeper_rs::DEFAULT_WINDOW_WIDTH,
-        glsweeper_rs::DEFAULT_WINDOW_HEIGHT,
+        window_width,
+        window_height,
         "GL Sweeper",
+        options.fullscreen,
+        options.gl_debug(),
+        config.clone(),
     );
     window.set_icon(include_bytes!("../icon.png").to_vec());
     window.init_gl();
 
-    let vs_code: String =
-        String::from_utf8(include_bytes!("../shaders/tile.vs").to_vec())?;
-    let fs_code: String =
-        String::from_utf8(include_bytes!("../shaders/tile.fs").to_vec())?;
-    let tile_shader = Shader::new(vs_code, fs_code);
-
-    let mut texture_atlas = Texture::new(glsweeper_rs::load_images(), 0);
-
-    texture_atlas.bind(0);
+    let mut shaders = match &options.watch_shaders {
+        Some(dir) => Shaders::watch(dir)?,
+        None => Shaders::new()?,
+    };
+    let minimap = Minimap::new();
+    let hud = Hud::new();
+    let mut text = TextRenderer::new();
 
-    let mut current_game =
-        Game::new(glsweeper_rs::DEFAULT_WIDTH, glsweeper_rs::DEFAULT_HEIGHT);
+    let mut theme = window.menu.theme().0;
+    let art = load_theme(window.menu.theme().1)
+        .or_else(|| load_theme(&Theme::default()))
+        .expect("Failed to load the bundled tiles.");
+    let mut background = art.background.unwrap_or(config.background);
+    let mut filter = window.menu.filter();
+    let mut tiles = Texture::tiles(&art, filter, 0);
 
-    tile_shader.use_program();
+    tiles.bind(0);
 
     while !window.should_close() {
         let start = std::time::Instant::now();
-        clear_draw(0.3, 0.3, 0.3, 1.0);
-        current_game.draw();
+        shaders.reload();
+
+        // Themes are read again each time they're picked, so edits to their
+        // art show without restarting.
+        if window.menu.theme().0 != theme {
+            theme = window.menu.theme().0;
+            if let Some(art) = load_theme(window.menu.theme().1) {
+                background = art.background.unwrap_or(config.background);
+                tiles = Texture::tiles(&art, filter, 0);
+                tiles.bind(0);
+            }
+        }
+        if window.menu.filter() != filter {
+            filter = window.menu.filter();
+            tiles.bind(0);
+            tiles.set_filter(filter);
+        }
+
+        let [r, g, b, a] = background;
+        clear_draw(r, g, b, a);
+
+        let layout = window.layout(&current_game);
+        layout.board.use_as_viewport();
+        // A paused board stays hidden, so it can't be studied off the clock.
+        if !current_game.is_paused() {
+            let time = window.get_time() as f32;
+
+            shaders.tile.use_program();
+            shaders.tile.set_sampler("atlasTexture", 0);
+            shaders.tile.set_mat4("view", &window.camera.view());
+            shaders.tile.set_float("time", time);
+            shaders.tile.set_bool("animate", config.animations);
+            shaders
+                .tile
+                .set_int("boardWidth", current_game.width as i32);
+            shaders
+                .tile
+                .set_int("boardHeight", current_game.height as i32);
+            current_game.draw(time);
+            if window.camera.is_zoomed() {
+                minimap.draw(
+                    &shaders.minimap,
+                    &window.camera,
+                    current_game.width,
+                    current_game.height,
+                );
+            }
+        }
+        if window.is_paused() && !window.menu.is_open() {
+            queue_paused(&mut text, &config, &layout.board);
+        }
+        queue_game_over(&mut text, &current_game, &layout.board);
+        text.draw(&shaders.text, &layout.board);
+
+        layout.hud.use_as_viewport();
+        hud.draw(&shaders.hud, &current_game, &layout.hud);
+
+        if window.menu.is_open() {
+            layout.window.use_as_viewport();
+            window.menu.draw(
+                &shaders.overlay,
+                &shaders.text,
+                &mut text,
+                &current_game,
+                &window.statistics,
+                &layout.window,
+            );
+        }
+
         window.update(&mut current_game);
         debug!("Frame took {} ms", start.elapsed().as_millis());
     }
 
     Ok(())
 }
+
+/// Loads the art of `theme`, or logs why it can't.
+fn load_theme(theme: &Theme) -> Option<TileArt> {
+    theme
+        .load()
+        .map_err(|error| {
+            warn!("Failed to load theme `{}`: {}", theme.name, error)
+        })
+        .ok()
+}
+
+/// Tells how to get back to the hidden board.
+fn queue_paused(text: &mut TextRenderer, config: &Config, board: &Rect) {
+    let size = (board.height / 10.0) as f32;
+    let centre = (board.width as f32 / 2.0, board.height as f32 / 2.0);
+    let style = TextStyle {
+        size,
+        colour: [1.0, 1.0, 1.0, 1.0],
+        align: Align::Center,
+    };
+    text.queue("Paused", (centre.0, centre.1 + size), &style);
+
+    if let Some(input) = config.bindings.inputs(Action::Pause).first() {
+        text.queue(
+            &format!("Press {} to resume", input.name()),
+            centre,
+            &TextStyle {
+                size: size / 2.5,
+                ..style
+            },
+        );
+    }
+}
+
+/// Writes the outcome across the middle of the board once the game is over.
+fn queue_game_over(text: &mut TextRenderer, game: &Game, board: &Rect) {
+    let (message, duration) = match game.state {
+        GameState::Won(duration) => ("You won!", duration),
+        GameState::Lost(duration) => ("You lost!", duration),
+        _ => return,
+    };
+    let message = format!("{}\n{:.1} s", message, duration.as_secs_f64());
+
+    let size = (board.height / 10.0) as f32;
+    let position = (board.width as f32 / 2.0, board.height as f32 / 2.0 + size);
+    let style = TextStyle {
+        size,
+        colour: [0.0, 0.0, 0.0, 0.6],
+        align: Align::Center,
+    };
+    // A drop shadow keeps the text readable over any tiles.
+    let offset = size / 16.0;
+    text.queue(&message, (position.0 + offset, position.1 - offset), &style);
+    text.queue(
+        &message,
+        position,
+        &TextStyle {
+            colour: [1.0, 1.0, 1.0, 1.0],
+            ..style
+        },
+    );
+}
@@ -0,0 +1,312 @@
+use std::{
+    collections::BTreeMap, fmt::Write, fs, io, path::PathBuf,
+    sync::mpsc::Receiver, time::Duration,
+};
+
+use log::{info, warn};
+use toml::{Table, Value};
+
+use crate::game::{Game, GameEvent};
+
+/// Results on one board size and mine count.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Record {
+    pub played: u32,
+    pub won: u32,
+    pub best_time: Option<Duration>,
+}
+
+impl Record {
+    /// Share of the games played that were won, between 0 and 1.
+    pub fn win_rate(&self) -> f64 {
+        match self.played {
+            0 => 0.0,
+            played => self.won as f64 / played as f64,
+        }
+    }
+}
+
+/// Finished games, kept in `statistics.toml` in the `glsweeper-rs` folder of
+/// the platform's data directory. Games are recorded as they end, from the
+/// events of the game being played. Games opened from a file are left out,
+/// as their clock starts over when they're opened.
+#[derive(Debug, Default)]
+pub struct Statistics {
+    records: BTreeMap<(isize, isize, isize), Record>,
+    events: Option<Receiver<GameEvent>>,
+}
+
+impl Statistics {
+    pub fn path() -> Option<PathBuf> {
+        dirs::data_dir()
+            .map(|dir| dir.join("glsweeper-rs").join("statistics.toml"))
+    }
+
+    /// Loads the saved statistics, starting afresh if there are none or
+    /// they can't be read.
+    pub fn load() -> Self {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                info!("Loading statistics from {}", path.display());
+                Self::parse(&text)
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                Self::default()
+            }
+            Err(error) => {
+                warn!("Failed to read {}: {}", path.display(), error);
+                Self::default()
+            }
+        }
+    }
+
+    /// Reads tables like `["16x16-40"]`, holding `played`, `won` and
+    /// `best_time` in seconds. Anything else is skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut statistics = Self::default();
+
+        let table = match text.parse::<Table>() {
+            Ok(table) => table,
+            Err(error) => {
+                warn!(
+                    "Ignoring statistics, they are not valid TOML: {}",
+                    error
+                );
+                return statistics;
+            }
+        };
+
+        for (name, record) in table {
+            let (board, record) = match (parse_board(&name), record) {
+                (Some(board), Value::Table(record)) => (board, record),
+                _ => {
+                    warn!("Ignoring statistics for `{}`", name);
+                    continue;
+                }
+            };
+            let count = |key| match record.get(key) {
+                Some(Value::Integer(count)) => u32::try_from(*count)
+                    .unwrap_or_else(|_| {
+                        warn!(
+                            "Ignoring `{}` for `{}`, {} is out of range",
+                            key, name, count
+                        );
+                        0
+                    }),
+                _ => 0,
+            };
+            let best_time = match record.get("best_time") {
+                Some(Value::Float(seconds)) if *seconds >= 0.0 => {
+                    Some(Duration::from_secs_f64(*seconds))
+                }
+                _ => None,
+            };
+
+            statistics.records.insert(
+                board,
+                Record {
+                    played: count("played"),
+                    won: count("won"),
+                    best_time,
+                },
+            );
+        }
+
+        statistics
+    }
+
+    pub fn save(&self) {
+        let path = match Self::path() {
+            Some(path) => path,
+            None => return,
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, self.to_toml()));
+
+        if let Err(error) = result {
+            warn!("Failed to save {}: {}", path.display(), error);
+        }
+    }
+
+    fn to_toml(&self) -> String {
+        let mut text = String::new();
+
+        for (&(width, height, mines), record) in &self.records {
+            let _ = writeln!(text, "[\"{}x{}-{}\"]", width, height, mines);
+            let _ = writeln!(text, "played = {}", record.played);
+            let _ = writeln!(text, "won = {}", record.won);
+            if let Some(best_time) = record.best_time {
+                let _ =
+                    writeln!(text, "best_time = {:?}", best_time.as_secs_f64());
+            }
+            text.push('\n');
+        }
+
+        text
+    }
+
+    /// Records the games `game` finished since the last call. The first call
+    /// subscribes to its events, which carry over when it restarts.
+    pub fn update(&mut self, game: &mut Game) {
+        let events = self.events.get_or_insert_with(|| game.subscribe());
+        // The time of each game won, or `None` for each game lost.
+        let results: Vec<_> = events
+            .try_iter()
+            .filter_map(|event| match event {
+                GameEvent::GameWon(duration) => Some(Some(duration)),
+                GameEvent::GameLost(_) => Some(None),
+                _ => None,
+            })
+            .collect();
+
+        if results.is_empty() {
+            return;
+        }
+        if game.is_loaded() {
+            info!("Not recording a game opened from a file");
+            return;
+        }
+
+        let record = self
+            .records
+            .entry((game.width, game.height, game.mine_count))
+            .or_default();
+
+        for won in results {
+            record.played += 1;
+            if let Some(duration) = won {
+                record.won += 1;
+                record.best_time = Some(match record.best_time {
+                    Some(best_time) => best_time.min(duration),
+                    None => duration,
+                });
+            }
+        }
+
+        self.save();
+    }
+
+    /// Results on a board of `width` by `height` tiles with `mines` mines.
+    pub fn record(&self, width: isize, height: isize, mines: isize) -> Record {
+        self.records
+            .get(&(width, height, mines))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Results on all boards together. There is no overall best time.
+    pub fn total(&self) -> Record {
+        self.records
+            .values()
+            .fold(Record::default(), |total, record| Record {
+                played: total.played.saturating_add(record.played),
+                won: total.won.saturating_add(record.won),
+                best_time: None,
+            })
+    }
+}
+
+fn parse_board(name: &str) -> Option<(isize, isize, isize)> {
+    let (size, mines) = name.split_once('-')?;
+    let (width, height) = size.split_once('x')?;
+
+    Some((
+        width.parse().ok()?,
+        height.parse().ok()?,
+        mines.parse().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_records() {
+        let statistics = Statistics::parse(
+            "[\"9x9-10\"]\nplayed = 3\nwon = 2\nbest_time = 12.5\n\n\
+             [\"16x16-40\"]\nplayed = 1\n",
+        );
+
+        assert_eq!(
+            statistics.record(9, 9, 10),
+            Record {
+                played: 3,
+                won: 2,
+                best_time: Some(Duration::from_secs_f64(12.5)),
+            }
+        );
+        assert_eq!(
+            statistics.record(16, 16, 40),
+            Record {
+                played: 1,
+                won: 0,
+                best_time: None,
+            }
+        );
+    }
+
+    #[test]
+    fn skips_what_it_cannot_read() {
+        let statistics = Statistics::parse(
+            "[\"9x9-10\"]\nplayed = -1\nwon = 2\nbest_time = -3.0\n\n\
+             [\"nine\"]\nplayed = 1\n",
+        );
+
+        assert_eq!(
+            statistics.record(9, 9, 10),
+            Record {
+                played: 0,
+                won: 2,
+                best_time: None,
+            }
+        );
+        assert_eq!(statistics.records.len(), 1);
+        assert!(Statistics::parse("played = ").records.is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let text = "[\"9x9-10\"]\nplayed = 3\nwon = 2\nbest_time = 12.5\n\n";
+
+        assert_eq!(Statistics::parse(text).to_toml(), text);
+    }
+
+    #[test]
+    fn totals_saturate() {
+        let statistics = Statistics::parse(&format!(
+            "[\"9x9-10\"]\nplayed = {max}\nwon = 1\n\n\
+             [\"16x16-40\"]\nplayed = 2\nwon = 1\n",
+            max = u32::MAX
+        ));
+
+        assert_eq!(
+            statistics.total(),
+            Record {
+                played: u32::MAX,
+                won: 2,
+                best_time: None,
+            }
+        );
+    }
+
+    #[test]
+    fn leaves_out_games_opened_from_a_file() {
+        let mut statistics = Statistics::default();
+        let mut game = Game::from_text("*100\n1100\n0000\n", None).unwrap();
+        statistics.update(&mut game);
+
+        game.reveal_at(3, 0);
+        statistics.update(&mut game);
+
+        assert_eq!(statistics.record(4, 3, 1), Record::default());
+    }
+}